@@ -3,11 +3,42 @@ use clap::Subcommand;
 #[derive(Subcommand)]
 pub enum Commands {
     #[clap(about = "Create an account. Use your email as your username.")]
-    Signup {},
+    Signup {
+        /// Email, signs up with email and password
+        #[clap(long)]
+        email: Option<String>,
+        /// Read the password from stdin
+        #[clap(long)]
+        password_stdin: bool,
+    },
     #[clap(about = "Login to your account. To create an account, use smb signup.")]
-    Login {},
+    Login {
+        /// Email, logs in with email and password
+        #[clap(long)]
+        email: Option<String>,
+        /// Read the password from stdin
+        #[clap(long)]
+        password_stdin: bool,
+        /// Answer yes to any confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
     #[clap(about = "Logout all session.")]
-    Logout {},
+    Logout {
+        /// Logout without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
     #[clap(about = "Forgot email? Use this command to reset your password.")]
-    Forgot {},
+    Forgot {
+        /// Email of your account
+        #[clap(long)]
+        email: Option<String>,
+        /// Code from the reset password email. Skips sending a new email.
+        #[clap(long)]
+        code: Option<String>,
+        /// Read the new password from stdin
+        #[clap(long)]
+        password_stdin: bool,
+    },
 }
@@ -1,21 +1,26 @@
-use crate::cli::CommandResult;
+use crate::cli::{prompt, CommandResult};
 use anyhow::Result;
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Input, Password};
-use reqwest::{Client, StatusCode};
-use serde::{Deserialize, Serialize};
+use reqwest::StatusCode;
 use smbpndk_model::forgot::{Args, Email, Param, UserUpdatePassword};
-use smbpndk_networking::{constants::PATH_USERS_PASSWORD, smb_base_url_builder};
-use smbpndk_utils::email_validation;
+use smbpndk_networking::{client::SmbClient, constants::PATH_USERS_PASSWORD, error::SmbError};
 use spinners::Spinner;
 
-pub async fn process_forgot() -> Result<CommandResult> {
-    println!("Provide your login credentials.");
-    let email = Input::<String>::with_theme(&ColorfulTheme::default())
-        .with_prompt("Email")
-        .validate_with(|email: &String| email_validation(email))
-        .interact()
-        .unwrap();
+pub async fn process_forgot(
+    client: &SmbClient,
+    email: Option<String>,
+    code: Option<String>,
+    password_stdin: bool,
+) -> Result<CommandResult> {
+    // The code has been sent already, go straight to the new password.
+    if let Some(code) = code {
+        return update_password(client, code, password_stdin).await;
+    }
+
+    if email.is_none() && prompt::is_interactive() {
+        eprintln!("Provide your login credentials.");
+    }
+    let email = prompt::email(email, "Email", "--email")?;
     let mut spinner = Spinner::new(
         spinners::Spinners::SimpleDotsScrolling,
         style("Checking email...").green().bold().to_string(),
@@ -25,33 +30,36 @@ pub async fn process_forgot() -> Result<CommandResult> {
         user: Email { email },
     };
 
-    let response = Client::new()
-        .post(build_smb_forgot_url())
+    let response = client
+        .post(&[PATH_USERS_PASSWORD])
         .json(&params)
         .send()
         .await?;
 
     match response.status() {
         StatusCode::OK => {
+            if !prompt::is_interactive() {
+                return Ok(CommandResult::success(
+                    spinner,
+                    "Check your email, then run smb account forgot --code <code> --password-stdin.",
+                ));
+            }
             spinner.stop_and_persist(
                 "✅",
                 "Check your email and input your code here.".to_owned(),
             );
-            input_code().await
+            input_code(client, password_stdin).await
+        }
+        _ => {
+            let error = SmbError::from_response(response).await;
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(anyhow::Error::new(error).context("Something wrong when trying to reset email."))
         }
-        _ => Ok(CommandResult {
-            spinner,
-            symbol: "😩".to_owned(),
-            msg: "Something wrong when trying to reset email.".to_owned(),
-        }),
     }
 }
 
-async fn input_code() -> Result<CommandResult> {
-    let security_code = Input::<String>::with_theme(&ColorfulTheme::default())
-        .with_prompt("Code")
-        .interact()
-        .unwrap();
+async fn input_code(client: &SmbClient, password_stdin: bool) -> Result<CommandResult> {
+    let security_code = prompt::text(None, "Code", "--code")?;
 
     Spinner::new(
         spinners::Spinners::SimpleDotsScrolling,
@@ -59,18 +67,15 @@ async fn input_code() -> Result<CommandResult> {
     )
     .stop_and_persist("✅", "Great. Now input your new password.".to_owned());
 
-    let new_password = Password::with_theme(&ColorfulTheme::default())
-        .with_prompt("Password")
-        .validate_with(|input: &String| -> Result<(), &str> {
-            if input.len() >= 6 {
-                Ok(())
-            } else {
-                Err("Password must be at least 6 characters")
-            }
-        })
-        .with_confirmation("Confirm password", "Passwords do not match")
-        .interact()
-        .unwrap();
+    update_password(client, security_code, password_stdin).await
+}
+
+async fn update_password(
+    client: &SmbClient,
+    security_code: String,
+    password_stdin: bool,
+) -> Result<CommandResult> {
+    let new_password = prompt::new_password(password_stdin, "Password")?;
     let password_confirmation = String::from(&new_password);
 
     // Should reuse this somehow
@@ -82,7 +87,7 @@ async fn input_code() -> Result<CommandResult> {
         },
     };
 
-    let spinner = Spinner::new(
+    let mut spinner = Spinner::new(
         spinners::Spinners::SimpleDotsScrolling,
         style("Updating your password...")
             .green()
@@ -90,39 +95,22 @@ async fn input_code() -> Result<CommandResult> {
             .to_string(),
     );
 
-    let response = Client::new()
-        .put(build_smb_forgot_url())
+    let response = client
+        .put(&[PATH_USERS_PASSWORD])
         .json(&params)
         .send()
         .await?;
 
-    #[derive(Debug, Serialize, Deserialize)]
-    struct Response {
-        status: i32,
-        email: Option<String>,
-    }
-
     match response.status() {
-        StatusCode::OK => Ok(CommandResult {
-            spinner,
-            symbol: "✅".to_owned(),
-            msg: "Your password has been updated. Login with your new password.".to_owned(),
-        }),
-        StatusCode::NOT_FOUND => Ok(CommandResult {
+        StatusCode::OK => Ok(CommandResult::success(
             spinner,
-            symbol: "😩".to_owned(),
-            msg: "URL not found.".to_owned(),
-        }),
-        _ => Ok(CommandResult {
-            spinner,
-            symbol: "😩".to_owned(),
-            msg: "Something wrong when trying to reset email.".to_owned(),
-        }),
+            "Your password has been updated. Login with your new password.",
+        )),
+        _ => {
+            let error = SmbError::from_response(response).await;
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(anyhow::Error::new(error)
+                .context("Something wrong when trying to reset your password."))
+        }
     }
 }
-
-fn build_smb_forgot_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_USERS_PASSWORD);
-    url_builder.build()
-}
@@ -2,24 +2,23 @@ use anyhow::{anyhow, Result};
 use console::style;
 use log::debug;
 use regex::Regex;
-use reqwest::{Client, Response, StatusCode};
+use reqwest::{Response, StatusCode};
 use smbpndk_model::account::SmbAuthorization;
 use smbpndk_networking::{
-    constants::{
-        GH_OAUTH_CLIENT_ID, GH_OAUTH_REDIRECT_HOST, GH_OAUTH_REDIRECT_PORT, PATH_AUTHORIZE,
-    },
-    smb_base_url_builder,
+    client::SmbClient,
+    constants::{GH_OAUTH_REDIRECT_HOST, GH_OAUTH_REDIRECT_PORT, PATH_AUTHORIZE},
+    credentials::{CredentialStore, FileCredentialStore},
+    environment::Environment,
 };
 use spinners::Spinner;
 use std::{
-    fs::{create_dir_all, OpenOptions},
     io::{BufRead, BufReader, Write},
     net::{TcpListener, TcpStream},
     sync::mpsc::{self, Receiver, Sender},
 };
 use url_builder::URLBuilder;
 
-pub async fn authorize_github() -> Result<SmbAuthorization> {
+pub async fn authorize_github(client: &SmbClient) -> Result<SmbAuthorization> {
     // Spin up a simple localhost server to listen for the GitHub OAuth callback
     // setup_oauth_callback_server();
     // Open the GitHub OAuth URL in the user's browser
@@ -31,7 +30,7 @@ pub async fn authorize_github() -> Result<SmbAuthorization> {
             .to_string(),
     );
 
-    let rx = match open::that(build_github_oauth_url()) {
+    let rx = match open::that(build_github_oauth_url(client.environment())) {
         Ok(_) => {
             let (tx, rx): (Sender<String>, Receiver<String>) = mpsc::channel();
             debug!(
@@ -57,7 +56,7 @@ pub async fn authorize_github() -> Result<SmbAuthorization> {
         Ok(code) => {
             debug!("Got code from channel: {:#?}", &code);
             //Err(anyhow!("Failed to get code from channel."))
-            process_connect_github(code).await
+            process_connect_github(client, code).await
         }
         Err(e) => {
             let error = anyhow!("Failed to get code from channel: {e}");
@@ -140,11 +139,10 @@ fn handle_connection(mut stream: TcpStream, tx: Sender<String>) {
 }
 
 // Get access token
-pub async fn process_connect_github(code: String) -> Result<SmbAuthorization> {
-    let response = Client::new()
-        .post(build_authorize_smb_url())
+pub async fn process_connect_github(client: &SmbClient, code: String) -> Result<SmbAuthorization> {
+    let response = client
+        .post(&[PATH_AUTHORIZE])
         .body(format!("gh_code={}", code))
-        .header("Accept", "application/json")
         .header("Content-Type", "application/x-www-form-urlencoded")
         .send()
         .await?;
@@ -187,14 +185,8 @@ pub async fn process_connect_github(code: String) -> Result<SmbAuthorization> {
     }
 }
 
-fn build_authorize_smb_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_AUTHORIZE);
-    url_builder.build()
-}
-
-fn build_github_oauth_url() -> String {
-    let mut url_builder = github_base_url_builder();
+fn build_github_oauth_url(environment: &Environment) -> String {
+    let mut url_builder = github_base_url_builder(environment);
     url_builder
         .add_route("login/oauth/authorize")
         .add_param("scope", "user")
@@ -202,14 +194,14 @@ fn build_github_oauth_url() -> String {
     url_builder.build()
 }
 
-fn github_base_url_builder() -> URLBuilder {
+fn github_base_url_builder(environment: &Environment) -> URLBuilder {
     let redirect_url = format!("{}:{}", GH_OAUTH_REDIRECT_HOST, GH_OAUTH_REDIRECT_PORT);
 
     let mut url_builder = URLBuilder::new();
     url_builder
         .set_protocol("https")
         .set_host("github.com")
-        .add_param("client_id", GH_OAUTH_CLIENT_ID)
+        .add_param("client_id", environment.github_client_id())
         .add_param("redirect_uri", &redirect_url);
     url_builder
 }
@@ -218,22 +210,7 @@ pub async fn save_token(response: &Response) -> Result<()> {
     let headers = response.headers();
     // println!("Headers: {:#?}", &headers);
     match headers.get("Authorization") {
-        Some(token) => {
-            debug!("{}", token.to_str()?);
-            match home::home_dir() {
-                Some(path) => {
-                    debug!("{}", path.to_str().unwrap());
-                    create_dir_all(path.join(".smb"))?;
-                    let mut file = OpenOptions::new()
-                        .create(true)
-                        .write(true)
-                        .open([path.to_str().unwrap(), "/.smb/token"].join(""))?;
-                    file.write_all(token.to_str()?.as_bytes())?;
-                    Ok(())
-                }
-                None => Err(anyhow!("Failed to get home directory.")),
-            }
-        }
+        Some(token) => FileCredentialStore::for_active_profile()?.save(token.to_str()?),
         None => Err(anyhow!("Failed to get token. Probably a backend issue.")),
     }
 }
@@ -3,13 +3,12 @@ use crate::{
         lib::{authorize_github, save_token},
         signup::{do_signup, SignupMethod},
     },
-    cli::CommandResult,
+    cli::{prompt, CommandResult, Status},
 };
 use anyhow::{anyhow, Result};
-use console::{style, Term};
-use dialoguer::{theme::ColorfulTheme, Confirm, Input, Password, Select};
+use console::style;
 use log::debug;
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use smbpndk_model::{
     account::{ErrorCode, GithubInfo, SmbAuthorization, User},
     forgot::{Param, UserUpdatePassword},
@@ -17,59 +16,59 @@ use smbpndk_model::{
     signup::{GithubEmail, Provider, SignupGithubParams, SignupUserGithub},
 };
 use smbpndk_networking::{
+    client::SmbClient,
     constants::{
         PATH_LINK_GITHUB_ACCOUNT, PATH_RESEND_CONFIRMATION, PATH_RESET_PASSWORD_INSTRUCTIONS,
         PATH_USERS_PASSWORD, PATH_USERS_SIGN_IN, PATH_USERS_SIGN_OUT,
     },
-    get_smb_token, smb_base_url_builder, smb_token_file_path,
+    credentials::{CredentialStore, FileCredentialStore},
+    error::SmbError,
+    smb_token_file_path,
 };
-use smbpndk_utils::email_validation;
 use spinners::Spinner;
-use std::fs::{self};
 
-pub async fn process_login() -> Result<CommandResult> {
+pub async fn process_login(
+    client: &SmbClient,
+    email: Option<String>,
+    password_stdin: bool,
+    yes: bool,
+) -> Result<CommandResult> {
     // Check if token file exists
     if smb_token_file_path().is_some() {
-        return Ok(CommandResult {
-            spinner: Spinner::new(
+        return Ok(CommandResult::success(
+            Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Loading...").green().bold().to_string(),
             ),
-            symbol: "✅".to_owned(),
-            msg: "You are already logged in. Please logout first.".to_owned(),
-        });
+            "You are already logged in. Please logout first.",
+        ));
     }
 
-    let signup_methods = vec![SignupMethod::Email, SignupMethod::GitHub];
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .items(&signup_methods)
-        .default(0)
-        .interact_on_opt(&Term::stderr())
-        .map(|i| signup_methods[i.unwrap()])
-        .unwrap();
+    // Logging in with GitHub needs a browser, so an email means email and password.
+    let selection = if email.is_some() {
+        SignupMethod::Email
+    } else {
+        prompt::select(&[SignupMethod::Email, SignupMethod::GitHub], "--email")?
+    };
 
     match selection {
-        SignupMethod::Email => login_with_email().await,
-        SignupMethod::GitHub => login_with_github().await,
+        SignupMethod::Email => login_with_email(client, email, password_stdin, yes).await,
+        SignupMethod::GitHub => login_with_github(client, yes).await,
     }
 }
 
-pub async fn process_logout() -> Result<CommandResult> {
+pub async fn process_logout(client: &SmbClient, yes: bool) -> Result<CommandResult> {
     // Logout if user confirms
-    if let Some(token_path) = smb_token_file_path() {
-        let confirm = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to logout? y/n")
-            .interact()
-            .unwrap();
+    if smb_token_file_path().is_some() {
+        let confirm = prompt::confirm(yes, "Do you want to logout?")?;
         if !confirm {
-            return Ok(CommandResult {
-                spinner: Spinner::new(
+            return Ok(CommandResult::cancelled(
+                Spinner::new(
                     spinners::Spinners::SimpleDotsScrolling,
                     style("Cancel operation.").green().bold().to_string(),
                 ),
-                symbol: "✅".to_owned(),
-                msg: "Doing nothing.".to_owned(),
-            });
+                "Doing nothing.",
+            ));
         }
 
         let mut spinner = Spinner::new(
@@ -78,38 +77,36 @@ pub async fn process_logout() -> Result<CommandResult> {
         );
 
         // Call backend
-        match do_process_logout().await {
+        match do_process_logout(client).await {
             Ok(_) => {
                 spinner.stop_and_persist("✅", "Done.".to_owned());
-                fs::remove_file(token_path)?;
-                Ok(CommandResult {
-                    spinner: Spinner::new(
+                FileCredentialStore::for_active_profile()?.delete()?;
+                Ok(CommandResult::success(
+                    Spinner::new(
                         spinners::Spinners::SimpleDotsScrolling,
                         style("Loading...").green().bold().to_string(),
                     ),
-                    symbol: "✅".to_owned(),
-                    msg: "You are now logged out!".to_owned(),
-                })
+                    "You are now logged out!",
+                ))
             }
-            Err(e) => Err(anyhow!("{e}")),
+            Err(e) => Err(e),
         }
     } else {
-        Ok(CommandResult {
-            spinner: Spinner::new(
+        Ok(CommandResult::success(
+            Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Loading...").green().bold().to_string(),
             ),
-            symbol: "😏".to_owned(),
-            msg: "You are not logged in.".to_owned(),
-        })
+            "You are not logged in.",
+        ))
     }
 }
 
 // Private functions
 
-async fn login_with_github() -> Result<CommandResult> {
-    match authorize_github().await {
-        Ok(result) => process_authorization(result).await,
+async fn login_with_github(client: &SmbClient, yes: bool) -> Result<CommandResult> {
+    match authorize_github(client).await {
+        Ok(result) => process_authorization(client, result, yes).await,
         Err(err) => {
             let error = anyhow!("Failed to authorize your GitHub account. {}", err);
             Err(error)
@@ -117,22 +114,35 @@ async fn login_with_github() -> Result<CommandResult> {
     }
 }
 
-async fn process_authorization(auth: SmbAuthorization) -> Result<CommandResult> {
+async fn process_authorization(
+    client: &SmbClient,
+    auth: SmbAuthorization,
+    yes: bool,
+) -> Result<CommandResult> {
     // What to do if not logged in with GitHub?
     // Check error_code first
     if let Some(error_code) = auth.error_code {
         debug!("{}", error_code);
         match error_code {
             ErrorCode::EmailNotFound => {
-                return create_new_account(auth.user_email, auth.user_info).await
+                return create_new_account(client, auth.user_email, auth.user_info, yes).await
+            }
+            ErrorCode::EmailUnverified => {
+                return send_email_verification(client, auth.user, yes).await
             }
-            ErrorCode::EmailUnverified => return send_email_verification(auth.user).await,
             ErrorCode::PasswordNotSet => {
                 // Only for email and password login
                 let error = anyhow!("Password not set.");
                 return Err(error);
             }
-            ErrorCode::GithubNotLinked => return connect_github_account(auth).await,
+            ErrorCode::GithubNotLinked => return connect_github_account(client, auth, yes).await,
+            ErrorCode::Unknown(_) => {
+                return Err(SmbError::Account {
+                    code: error_code,
+                    message: auth.message,
+                }
+                .into())
+            }
         }
     }
 
@@ -144,11 +154,10 @@ async fn process_authorization(auth: SmbAuthorization) -> Result<CommandResult>
             style("Logging you in...").green().bold().to_string(),
         );
         // We're logged in with GitHub.
-        return Ok(CommandResult {
+        return Ok(CommandResult::success(
             spinner,
-            symbol: "✅".to_owned(),
-            msg: format!("You are logged in with GitHub as {}.", user.email),
-        });
+            format!("You are logged in with GitHub as {}.", user.email),
+        ));
     }
 
     let error: anyhow::Error = anyhow!("Failed to login with GitHub.");
@@ -156,13 +165,12 @@ async fn process_authorization(auth: SmbAuthorization) -> Result<CommandResult>
 }
 
 async fn create_new_account(
+    client: &SmbClient,
     user_email: Option<GithubEmail>,
     user_info: Option<GithubInfo>,
+    yes: bool,
 ) -> Result<CommandResult> {
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Do you want to create a new account?")
-        .interact()
-        .unwrap();
+    let confirm = prompt::confirm(yes, "Do you want to create a new account?")?;
 
     // Create account if user confirms
     if !confirm {
@@ -170,11 +178,10 @@ async fn create_new_account(
             spinners::Spinners::SimpleDotsScrolling,
             style("Logging you in...").green().bold().to_string(),
         );
-        return Ok(CommandResult {
+        return Ok(CommandResult::cancelled(
             spinner,
-            symbol: "✅".to_owned(),
-            msg: "Please accept to link your GitHub account.".to_owned(),
-        });
+            "Please accept to link your GitHub account.",
+        ));
     }
 
     if let (Some(email), Some(info)) = (user_email, user_info) {
@@ -188,19 +195,20 @@ async fn create_new_account(
             },
         };
 
-        return do_signup(&params).await;
+        return do_signup(client, &params).await;
     }
 
     Err(anyhow!("Shouldn't be here."))
 }
 
-async fn send_email_verification(user: Option<User>) -> Result<CommandResult> {
+async fn send_email_verification(
+    client: &SmbClient,
+    user: Option<User>,
+    yes: bool,
+) -> Result<CommandResult> {
     // Return early if user is null
     if let Some(user) = user {
-        let confirm = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to send a new verification email?")
-            .interact()
-            .unwrap();
+        let confirm = prompt::confirm(yes, "Do you want to send a new verification email?")?;
 
         // Send verification email if user confirms
         if !confirm {
@@ -208,20 +216,16 @@ async fn send_email_verification(user: Option<User>) -> Result<CommandResult> {
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Cancel operation.").green().bold().to_string(),
             );
-            return Ok(CommandResult {
-                spinner,
-                symbol: "✅".to_owned(),
-                msg: "Doing nothing.".to_owned(),
-            });
+            return Ok(CommandResult::cancelled(spinner, "Doing nothing."));
         }
-        resend_email_verification(user).await
+        resend_email_verification(client, user).await
     } else {
         let error = anyhow!("Failed to get user.");
         Err(error)
     }
 }
 
-async fn resend_email_verification(user: User) -> Result<CommandResult> {
+async fn resend_email_verification(client: &SmbClient, user: User) -> Result<CommandResult> {
     let spinner = Spinner::new(
         spinners::Spinners::SimpleDotsScrolling,
         style("Sending verification email...")
@@ -230,20 +234,15 @@ async fn resend_email_verification(user: User) -> Result<CommandResult> {
             .to_string(),
     );
 
-    let response = Client::new()
-        .post(build_smb_resend_email_verification_url())
+    let response = client
+        .post(&[PATH_RESEND_CONFIRMATION])
         .body(format!("id={}", user.id))
-        .header("Accept", "application/json")
         .header("Content-Type", "application/x-www-form-urlencoded")
         .send()
         .await?;
 
     match response.status() {
-        reqwest::StatusCode::OK => Ok(CommandResult {
-            spinner,
-            symbol: "✅".to_owned(),
-            msg: "Verification email sent!".to_owned(),
-        }),
+        reqwest::StatusCode::OK => Ok(CommandResult::success(spinner, "Verification email sent!")),
         _ => {
             let error = anyhow!("Failed to send verification email.");
             Err(error)
@@ -251,11 +250,12 @@ async fn resend_email_verification(user: User) -> Result<CommandResult> {
     }
 }
 
-async fn connect_github_account(auth: SmbAuthorization) -> Result<CommandResult> {
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Do you want to link your GitHub account?")
-        .interact()
-        .unwrap();
+async fn connect_github_account(
+    client: &SmbClient,
+    auth: SmbAuthorization,
+    yes: bool,
+) -> Result<CommandResult> {
+    let confirm = prompt::confirm(yes, "Do you want to link your GitHub account?")?;
 
     // Link GitHub account if user confirms
     if !confirm {
@@ -263,11 +263,7 @@ async fn connect_github_account(auth: SmbAuthorization) -> Result<CommandResult>
             spinners::Spinners::SimpleDotsScrolling,
             style("Cancel operation.").green().bold().to_string(),
         );
-        return Ok(CommandResult {
-            spinner,
-            symbol: "✅".to_owned(),
-            msg: "Doing nothing.".to_owned(),
-        });
+        return Ok(CommandResult::cancelled(spinner, "Doing nothing."));
     }
 
     let spinner = Spinner::new(
@@ -278,20 +274,14 @@ async fn connect_github_account(auth: SmbAuthorization) -> Result<CommandResult>
             .to_string(),
     );
 
-    let response = Client::new()
-        .post(build_smb_connect_github_url())
+    let response = client
+        .post(&[PATH_LINK_GITHUB_ACCOUNT])
         .json(&auth)
-        .header("Accept", "application/json")
-        .header("Content-Type", "application/x-www-form-urlencoded")
         .send()
         .await?;
 
     match response.status() {
-        reqwest::StatusCode::OK => Ok(CommandResult {
-            spinner,
-            symbol: "✅".to_owned(),
-            msg: "GitHub account linked!".to_owned(),
-        }),
+        reqwest::StatusCode::OK => Ok(CommandResult::success(spinner, "GitHub account linked!")),
         _ => {
             let error = anyhow!("Failed to link GitHub account.");
             Err(error)
@@ -299,21 +289,21 @@ async fn connect_github_account(auth: SmbAuthorization) -> Result<CommandResult>
     }
 }
 
-async fn login_with_email() -> Result<CommandResult> {
-    println!("Provide your login credentials.");
-    let username = Input::<String>::with_theme(&ColorfulTheme::default())
-        .with_prompt("Email")
-        .validate_with(|email: &String| email_validation(email))
-        .interact()
-        .unwrap();
-    let password = Password::with_theme(&ColorfulTheme::default())
-        .with_prompt("Password")
-        .interact()
-        .unwrap();
-    do_process_login(LoginArgs { username, password }).await
+async fn login_with_email(
+    client: &SmbClient,
+    email: Option<String>,
+    password_stdin: bool,
+    yes: bool,
+) -> Result<CommandResult> {
+    if email.is_none() && prompt::is_interactive() {
+        eprintln!("Provide your login credentials.");
+    }
+    let username = prompt::email(email, "Email", "--email")?;
+    let password = prompt::password(password_stdin, "Password")?;
+    do_process_login(client, LoginArgs { username, password }, yes).await
 }
 
-async fn do_process_login(args: LoginArgs) -> Result<CommandResult> {
+async fn do_process_login(client: &SmbClient, args: LoginArgs, yes: bool) -> Result<CommandResult> {
     let login_params = LoginParams {
         user: UserParam {
             email: args.username,
@@ -321,8 +311,8 @@ async fn do_process_login(args: LoginArgs) -> Result<CommandResult> {
         },
     };
 
-    let response = Client::new()
-        .post(build_smb_login_url())
+    let response = client
+        .post(&[PATH_USERS_SIGN_IN])
         .json(&login_params)
         .send()
         .await?;
@@ -331,43 +321,48 @@ async fn do_process_login(args: LoginArgs) -> Result<CommandResult> {
         StatusCode::OK => {
             // Login successful
             save_token(&response).await?;
-            Ok(CommandResult {
-                spinner: Spinner::new(
+            Ok(CommandResult::success(
+                Spinner::new(
                     spinners::Spinners::SimpleDotsScrolling,
                     style("Loading...").green().bold().to_string(),
                 ),
-                symbol: "✅".to_owned(),
-                msg: "You are now logged in!".to_owned(),
-            })
+                "You are now logged in!",
+            ))
         }
         StatusCode::NOT_FOUND => {
             // Account not found and we show signup option
-            Ok(CommandResult {
-                spinner: Spinner::new(
+            Ok(CommandResult::failure(
+                Spinner::new(
                     spinners::Spinners::SimpleDotsScrolling,
                     style("Account not found.").green().bold().to_string(),
                 ),
-                symbol: "✅".to_owned(),
-                msg: "Please signup!".to_owned(),
-            })
+                Status::NotFound,
+                "Please signup!",
+            ))
         }
         StatusCode::UNPROCESSABLE_ENTITY => {
             // Account found but email not verified / password not set
             let result: SmbAuthorization = response.json().await?;
             // println!("Result: {:#?}", &result);
-            verify_or_set_password(result).await
+            verify_or_set_password(client, result, yes).await
         }
         _ => Err(anyhow!("Login failed. Check your username and password.")),
     }
 }
 
-async fn verify_or_set_password(result: SmbAuthorization) -> Result<CommandResult> {
+async fn verify_or_set_password(
+    client: &SmbClient,
+    result: SmbAuthorization,
+    yes: bool,
+) -> Result<CommandResult> {
     match result.error_code {
         Some(error_code) => {
             debug!("{}", error_code);
             match error_code {
-                ErrorCode::EmailUnverified => send_email_verification(result.user).await,
-                ErrorCode::PasswordNotSet => send_reset_password(result.user).await,
+                ErrorCode::EmailUnverified => {
+                    send_email_verification(client, result.user, yes).await
+                }
+                ErrorCode::PasswordNotSet => send_reset_password(client, result.user, yes).await,
                 _ => Err(anyhow!("Shouldn't be here.")),
             }
         }
@@ -375,13 +370,14 @@ async fn verify_or_set_password(result: SmbAuthorization) -> Result<CommandResul
     }
 }
 
-async fn send_reset_password(user: Option<User>) -> Result<CommandResult> {
+async fn send_reset_password(
+    client: &SmbClient,
+    user: Option<User>,
+    yes: bool,
+) -> Result<CommandResult> {
     // Return early if user is null
     if let Some(user) = user {
-        let confirm = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to reset your password?")
-            .interact()
-            .unwrap();
+        let confirm = prompt::confirm(yes, "Do you want to reset your password?")?;
 
         // Send verification email if user confirms
         if !confirm {
@@ -389,20 +385,19 @@ async fn send_reset_password(user: Option<User>) -> Result<CommandResult> {
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Cancel operation.").green().bold().to_string(),
             );
-            return Ok(CommandResult {
-                spinner,
-                symbol: "✅".to_owned(),
-                msg: "Doing nothing.".to_owned(),
-            });
+            return Ok(CommandResult::cancelled(spinner, "Doing nothing."));
         }
-        resend_reset_password_instruction(user).await
+        resend_reset_password_instruction(client, user).await
     } else {
         let error = anyhow!("Failed to get user.");
         Err(error)
     }
 }
 
-async fn resend_reset_password_instruction(user: User) -> Result<CommandResult> {
+async fn resend_reset_password_instruction(
+    client: &SmbClient,
+    user: User,
+) -> Result<CommandResult> {
     let mut spinner = Spinner::new(
         spinners::Spinners::SimpleDotsScrolling,
         style("Sending reset password instruction...")
@@ -410,10 +405,9 @@ async fn resend_reset_password_instruction(user: User) -> Result<CommandResult>
             .bold()
             .to_string(),
     );
-    let response = Client::new()
-        .post(build_smb_resend_reset_password_instructions_url())
+    let response = client
+        .post(&[PATH_RESET_PASSWORD_INSTRUCTIONS])
         .body(format!("id={}", user.id))
-        .header("Accept", "application/json")
         .header("Content-Type", "application/x-www-form-urlencoded")
         .send()
         .await?;
@@ -424,7 +418,7 @@ async fn resend_reset_password_instruction(user: User) -> Result<CommandResult>
                 "✅",
                 "Reset password instruction sent! Please check your email.".to_owned(),
             );
-            input_reset_password_token().await
+            input_reset_password_token(client).await
         }
         _ => {
             let error = anyhow!("Failed to send reset password instruction.");
@@ -433,16 +427,14 @@ async fn resend_reset_password_instruction(user: User) -> Result<CommandResult>
     }
 }
 
-async fn input_reset_password_token() -> Result<CommandResult> {
-    let token = Input::<String>::with_theme(&ColorfulTheme::default())
-        .with_prompt("Input reset password token")
-        .interact()
-        .unwrap();
-    let password = Password::with_theme(&ColorfulTheme::default())
-        .with_prompt("New password.")
-        .with_confirmation("Repeat password.", "Error: the passwords don't match.")
-        .interact()
-        .unwrap();
+async fn input_reset_password_token(client: &SmbClient) -> Result<CommandResult> {
+    // Without a terminal, finish the reset with smb account forgot --code.
+    let token = prompt::text(
+        None,
+        "Input reset password token",
+        "the reset token. Finish with smb account forgot --code",
+    )?;
+    let password = prompt::new_password(false, "New password")?;
 
     let spinner = Spinner::new(
         spinners::Spinners::SimpleDotsScrolling,
@@ -459,20 +451,14 @@ async fn input_reset_password_token() -> Result<CommandResult> {
         },
     };
 
-    let response = Client::new()
-        .put(build_smb_reset_password_url())
+    let response = client
+        .put(&[PATH_USERS_PASSWORD])
         .json(&params)
-        .header("Accept", "application/json")
-        .header("Content-Type", "application/x-www-form-urlencoded")
         .send()
         .await?;
 
     match response.status() {
-        StatusCode::OK => Ok(CommandResult {
-            spinner,
-            symbol: "✅".to_owned(),
-            msg: "Password reset!".to_owned(),
-        }),
+        StatusCode::OK => Ok(CommandResult::success(spinner, "Password reset!")),
         _ => {
             let error = anyhow!("Failed to reset password.");
             Err(error)
@@ -480,55 +466,20 @@ async fn input_reset_password_token() -> Result<CommandResult> {
     }
 }
 
-async fn do_process_logout() -> Result<()> {
-    let token = get_smb_token().await?;
+async fn do_process_logout(client: &SmbClient) -> Result<()> {
+    if client.token().is_none() {
+        return Err(SmbError::Unauthorized.into());
+    }
 
-    let response = Client::new()
-        .delete(build_smb_logout_url())
-        .header("Authorization", token)
-        .header("Accept", "application/json")
+    let response = client
+        .delete(&[PATH_USERS_SIGN_OUT])
         .header("Content-Type", "application/x-www-form-urlencoded")
         .send()
         .await?;
 
     match response.status() {
         StatusCode::OK => Ok(()),
-        _ => Err(anyhow!("Failed to logout.")),
+        _ => Err(anyhow::Error::new(SmbError::from_response(response).await)
+            .context("Failed to logout.")),
     }
 }
-
-fn build_smb_login_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_USERS_SIGN_IN);
-    url_builder.build()
-}
-
-fn build_smb_logout_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_USERS_SIGN_OUT);
-    url_builder.build()
-}
-
-fn build_smb_resend_email_verification_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_RESEND_CONFIRMATION);
-    url_builder.build()
-}
-
-fn build_smb_resend_reset_password_instructions_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_RESET_PASSWORD_INSTRUCTIONS);
-    url_builder.build()
-}
-
-fn build_smb_reset_password_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_USERS_PASSWORD);
-    url_builder.build()
-}
-
-fn build_smb_connect_github_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_LINK_GITHUB_ACCOUNT);
-    url_builder.build()
-}
@@ -12,12 +12,24 @@ use self::{
 };
 use crate::cli::CommandResult;
 use anyhow::Result;
+use smbpndk_networking::client::SmbClient;
 
-pub async fn process_account(commands: Commands) -> Result<CommandResult> {
+pub async fn process_account(client: &SmbClient, commands: Commands) -> Result<CommandResult> {
     match commands {
-        Commands::Signup {} => process_signup().await,
-        Commands::Login {} => process_login().await,
-        Commands::Logout {} => process_logout().await,
-        Commands::Forgot {} => process_forgot().await,
+        Commands::Signup {
+            email,
+            password_stdin,
+        } => process_signup(client, email, password_stdin).await,
+        Commands::Login {
+            email,
+            password_stdin,
+            yes,
+        } => process_login(client, email, password_stdin, yes).await,
+        Commands::Logout { yes } => process_logout(client, yes).await,
+        Commands::Forgot {
+            email,
+            code,
+            password_stdin,
+        } => process_forgot(client, email, code, password_stdin).await,
     }
 }
@@ -1,66 +1,53 @@
 use super::SignupMethod;
-use crate::{account::lib::authorize_github, cli::CommandResult};
+use crate::{
+    account::lib::authorize_github,
+    cli::{prompt, CommandResult},
+};
 use anyhow::{anyhow, Result};
-use console::{style, Term};
-use dialoguer::{theme::ColorfulTheme, Input, Password, Select};
+use console::style;
 use log::debug;
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use serde::Serialize;
 use smbpndk_model::signup::{SignupEmailParams, SignupResult, SignupUserEmail};
-use smbpndk_networking::{constants::PATH_USERS, smb_base_url_builder, smb_token_file_path};
-use smbpndk_utils::email_validation;
+use smbpndk_networking::{client::SmbClient, constants::PATH_USERS, smb_token_file_path};
 use spinners::Spinner;
 
-pub async fn process_signup() -> Result<CommandResult> {
+pub async fn process_signup(
+    client: &SmbClient,
+    email: Option<String>,
+    password_stdin: bool,
+) -> Result<CommandResult> {
     // Check if token file exists
     if smb_token_file_path().is_some() {
-        return Ok(CommandResult {
-            spinner: Spinner::new(
+        return Ok(CommandResult::success(
+            Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Loading...").green().bold().to_string(),
             ),
-            symbol: "✅".to_owned(),
-            msg: "You are already logged in. Please logout first.".to_owned(),
-        });
+            "You are already logged in. Please logout first.",
+        ));
     }
 
-    let signup_methods = vec![SignupMethod::Email, SignupMethod::GitHub];
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .items(&signup_methods)
-        .default(0)
-        .interact_on_opt(&Term::stderr())
-        .map(|i| signup_methods[i.unwrap()])
-        .unwrap();
+    // Signing up with GitHub needs a browser, so an email means email and password.
+    let selection = if email.is_some() {
+        SignupMethod::Email
+    } else {
+        prompt::select(&[SignupMethod::Email, SignupMethod::GitHub], "--email")?
+    };
 
     match selection {
-        SignupMethod::Email => signup_with_email(None).await,
-        SignupMethod::GitHub => signup_with_github().await,
+        SignupMethod::Email => signup_with_email(client, email, password_stdin).await,
+        SignupMethod::GitHub => signup_with_github(client).await,
     }
 }
 
-pub async fn signup_with_email(email: Option<String>) -> Result<CommandResult> {
-    let email = if let Some(email) = email {
-        email
-    } else {
-        Input::<String>::with_theme(&ColorfulTheme::default())
-            .with_prompt("Username")
-            .validate_with(|email: &String| email_validation(email))
-            .interact()
-            .unwrap()
-    };
-
-    let password = Password::with_theme(&ColorfulTheme::default())
-        .with_prompt("Password")
-        .validate_with(|input: &String| -> Result<(), &str> {
-            if input.len() >= 6 {
-                Ok(())
-            } else {
-                Err("Password must be at least 6 characters")
-            }
-        })
-        .with_confirmation("Confirm password", "Passwords do not match")
-        .interact()
-        .unwrap();
+pub async fn signup_with_email(
+    client: &SmbClient,
+    email: Option<String>,
+    password_stdin: bool,
+) -> Result<CommandResult> {
+    let email = prompt::email(email, "Username", "--email")?;
+    let password = prompt::new_password(password_stdin, "Password")?;
 
     let spinner = Spinner::new(
         spinners::Spinners::BouncingBall,
@@ -71,35 +58,27 @@ pub async fn signup_with_email(email: Option<String>) -> Result<CommandResult> {
         user: SignupUserEmail { email, password },
     };
 
-    match do_signup(&params).await {
-        Ok(_) => Ok(CommandResult {
-            spinner,
-            symbol: style("✅".to_string()).for_stderr().green().to_string(),
-            msg: "You are signed up! Check your email to confirm your account.".to_owned(),
-        }),
-        Err(e) => Ok(CommandResult {
-            spinner,
-            symbol: style("✘".to_string()).for_stderr().red().to_string(),
-            msg: format!("{e}"),
-        }),
-    }
+    do_signup(client, &params).await?;
+    Ok(CommandResult::success(
+        spinner,
+        "You are signed up! Check your email to confirm your account.",
+    ))
 }
 
-async fn signup_with_github() -> Result<CommandResult> {
-    match authorize_github().await {
+async fn signup_with_github(client: &SmbClient) -> Result<CommandResult> {
+    match authorize_github(client).await {
         Ok(code) => {
             debug!("Code: {:#?}", code);
-            Ok(CommandResult {
-                spinner: Spinner::new(
+            Ok(CommandResult::success(
+                Spinner::new(
                     spinners::Spinners::BouncingBall,
                     style("Requesting GitHub token...")
                         .green()
                         .bold()
                         .to_string(),
                 ),
-                symbol: style("✅".to_string()).for_stderr().green().to_string(),
-                msg: "Finished requesting GitHub token!".to_owned(),
-            })
+                "Finished requesting GitHub token!",
+            ))
         }
         Err(e) => {
             let error = anyhow!("Failed to get code from channel: {e}");
@@ -108,24 +87,22 @@ async fn signup_with_github() -> Result<CommandResult> {
     }
 }
 
-pub async fn do_signup<T: Serialize + ?Sized>(args: &T) -> Result<CommandResult> {
+pub async fn do_signup<T: Serialize + ?Sized>(
+    client: &SmbClient,
+    args: &T,
+) -> Result<CommandResult> {
     let spinner = Spinner::new(
         spinners::Spinners::BouncingBall,
         style("Signing you up...").green().bold().to_string(),
     );
 
-    let response = Client::new()
-        .post(build_smb_signup_url())
-        .json(&args)
-        .send()
-        .await?;
+    let response = client.post(&[PATH_USERS]).json(&args).send().await?;
 
     match response.status() {
-        StatusCode::OK => Ok(CommandResult {
+        StatusCode::OK => Ok(CommandResult::success(
             spinner,
-            symbol: "✅".to_owned(),
-            msg: "Your account has been created. Check email for verification link.".to_owned(),
-        }),
+            "Your account has been created. Check email for verification link.",
+        )),
         StatusCode::UNPROCESSABLE_ENTITY => {
             let result: SignupResult = response.json().await?;
             let error = anyhow!("Failed to signup: {}", result.status.message);
@@ -137,9 +114,3 @@ pub async fn do_signup<T: Serialize + ?Sized>(args: &T) -> Result<CommandResult>
         }
     }
 }
-
-fn build_smb_signup_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route(PATH_USERS);
-    url_builder.build()
-}
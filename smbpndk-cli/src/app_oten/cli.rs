@@ -0,0 +1,183 @@
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[clap(about = "Add new Oten app. Its secret is shown only once.")]
+    New {
+        /// App name
+        #[clap(short, long)]
+        name: Option<String>,
+        /// App description
+        #[clap(short, long)]
+        description: Option<String>,
+        /// Redirect URI to allow. Repeat for more.
+        #[clap(long = "redirect-uri")]
+        redirect_uris: Vec<String>,
+        /// Scope the app may ask for. Repeat for more.
+        #[clap(long = "scope")]
+        scopes: Vec<String>,
+    },
+
+    #[clap(about = "List all Oten apps of the project.")]
+    List {},
+
+    #[clap(about = "Show detail of an Oten app.")]
+    Show {
+        /// App Id
+        id: String,
+    },
+
+    #[clap(about = "Delete an Oten app.")]
+    Delete {
+        /// App Id
+        id: String,
+        /// Delete without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Use Oten app for current CLI session.")]
+    Use {
+        /// App Id
+        id: String,
+    },
+
+    #[clap(about = "Replace the secret of an Oten app. The old secret stops working.")]
+    RotateSecret {
+        /// App Id
+        id: String,
+        /// Rotate without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Manage where an Oten app may send users back to after authorization.")]
+    Redirect {
+        #[clap(subcommand)]
+        command: RedirectCommands,
+    },
+
+    #[clap(about = "Manage the scopes an Oten app may ask for.")]
+    Scopes {
+        #[clap(subcommand)]
+        command: ScopesCommands,
+    },
+
+    #[clap(about = "Manage the end users of an Oten app.")]
+    Users {
+        /// App Id. Defaults to the app in use.
+        #[clap(long, global = true)]
+        app: Option<String>,
+
+        #[clap(subcommand)]
+        command: UsersCommands,
+    },
+
+    #[clap(
+        about = "Get a token from an Oten app and show what is in it. Defaults to the app in use."
+    )]
+    Try {
+        /// App Id, which is also its OAuth client id
+        app: Option<String>,
+        /// Read the app secret from stdin
+        #[clap(long)]
+        secret_stdin: bool,
+        /// Use the client credentials grant instead of opening a browser
+        #[clap(long)]
+        client_credentials: bool,
+        /// Scopes to ask for, separated by spaces
+        #[clap(long)]
+        scope: Option<String>,
+        /// Port of the local callback listener. The app needs http://localhost:<port>/callback as a redirect URI.
+        #[clap(long, default_value_t = 8809)]
+        port: u16,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RedirectCommands {
+    #[clap(about = "Allow redirect URIs on an app.")]
+    Add {
+        /// App Id
+        id: String,
+        /// Redirect URIs, e.g. https://example.com/callback
+        #[clap(required = true)]
+        uris: Vec<String>,
+    },
+
+    #[clap(about = "Stop allowing redirect URIs on an app.")]
+    Remove {
+        /// App Id
+        id: String,
+        /// Redirect URIs
+        #[clap(required = true)]
+        uris: Vec<String>,
+    },
+
+    #[clap(about = "List the redirect URIs of an app.")]
+    List {
+        /// App Id
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ScopesCommands {
+    #[clap(about = "Replace the scopes an app may ask for.")]
+    Set {
+        /// App Id
+        id: String,
+        /// Scopes, e.g. read write
+        #[clap(required_unless_present = "none")]
+        scopes: Vec<String>,
+        /// Allow no scopes at all
+        #[clap(long, conflicts_with = "scopes")]
+        none: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum UsersCommands {
+    #[clap(about = "List users of the app, a page at a time.")]
+    List {
+        /// Only users whose email contains this
+        #[clap(long)]
+        email: Option<String>,
+        /// Page to show, starting at 1
+        #[clap(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        page: u32,
+        /// Users per page
+        #[clap(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=100))]
+        per_page: u32,
+        /// Fetch every page
+        #[clap(long, conflicts_with = "page")]
+        all: bool,
+    },
+
+    #[clap(about = "Show detail of a user.")]
+    Show {
+        /// User Id
+        id: String,
+    },
+
+    #[clap(about = "Stop a user from signing in and revoke their tokens.")]
+    Disable {
+        /// User Id
+        id: String,
+    },
+
+    #[clap(about = "Let a disabled user sign in again.")]
+    Enable {
+        /// User Id
+        id: String,
+    },
+
+    #[clap(about = "Delete a user.")]
+    Delete {
+        /// User Id
+        id: String,
+        /// Delete without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+}
@@ -0,0 +1,293 @@
+pub mod cli;
+mod oauth;
+mod redirect;
+mod users;
+
+use self::{
+    cli::Commands,
+    oauth::process_try,
+    redirect::{check_redirect_uris, check_scopes, process_redirect, process_scopes},
+    users::process_users,
+};
+use crate::{
+    cli::{
+        output::{output_format, print_list, print_one, OutputFormat, Tabular},
+        prompt::{self, MissingInput},
+        CommandResult,
+    },
+    project::project_id_or_current,
+};
+use anyhow::Result;
+use console::style;
+use serde_json::json;
+use smbpndk_model::{
+    app_auth::{AuthApp, AuthAppCreate},
+    project::Config,
+};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_oten::{
+    create_auth_app, delete_auth_app, get_auth_app, get_auth_apps, rotate_secret,
+};
+use smbpndk_utils::{get_config, write_config};
+use spinners::Spinner;
+
+/// `project_id` is the `--project-id` given on the command line, if any.
+pub async fn process_oten(
+    client: &SmbClient,
+    project_id: Option<String>,
+    commands: Commands,
+) -> Result<CommandResult> {
+    // Trying an app only needs its credentials, not the project it belongs to.
+    if let Commands::Try {
+        app,
+        secret_stdin,
+        client_credentials,
+        scope,
+        port,
+    } = commands
+    {
+        return process_try(client, app, secret_stdin, client_credentials, scope, port).await;
+    }
+    let project_id = project_id_or_current(project_id).await?;
+
+    match commands {
+        Commands::New {
+            name,
+            description,
+            redirect_uris,
+            scopes,
+        } => {
+            let name = prompt::text(name, "App name", "--name")?;
+            let description = prompt::text(description, "Description", "--description")?;
+            check_redirect_uris(&redirect_uris)?;
+            check_scopes(&scopes)?;
+            let auth_app = AuthAppCreate {
+                name,
+                description,
+                redirect_uris,
+                scopes,
+            };
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Creating an app...").green().bold().to_string(),
+            );
+            match create_auth_app(client, &project_id, auth_app).await {
+                Ok(auth_app) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_with_secret(&auth_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!(
+                            "{} has been created. Store the secret now, it will not be shown again.",
+                            auth_app.name
+                        ),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::List {} => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_auth_apps(client, &project_id).await {
+                Ok(auth_apps) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let msg = if auth_apps.is_empty() {
+                        "No apps found."
+                    } else {
+                        "Showing all apps."
+                    };
+                    print_list(&auth_apps)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Show { id } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_auth_app(client, &project_id, &id).await {
+                Ok(auth_app) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let message = format!("Showing app {}.", &auth_app.name);
+                    print_one(&auth_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        message,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Delete { id, yes } => {
+            let confirmation = prompt::confirm(yes, &format!("Delete app {id}?"))?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Deleting app...").green().bold().to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            match delete_auth_app(client, &project_id, &id).await {
+                Ok(_) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    // Do not keep using an app that is gone.
+                    let config = get_config().await?;
+                    if config.current_auth_app.as_ref().map(|app| &app.id) == Some(&id) {
+                        write_config(Config {
+                            current_auth_app: None,
+                            ..config
+                        })?;
+                    }
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        "App has been deleted.",
+                    )
+                    .with_data(json!({ "id": id })))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Use { id } => {
+            let auth_app = get_auth_app(client, &project_id, &id).await?;
+
+            let data = serde_json::to_value(&auth_app)?;
+            let config = Config {
+                // Secrets never go into the config file.
+                current_auth_app: Some(AuthApp {
+                    secret: None,
+                    ..auth_app
+                }),
+                ..get_config().await?
+            };
+
+            let spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            write_config(config)?;
+
+            Ok(CommandResult::success(spinner, "Use app successful.").with_data(data))
+        }
+        Commands::RotateSecret { id, yes } => {
+            let confirmation = prompt::confirm(
+                yes,
+                &format!("The current secret of app {id} stops working. Rotate it?"),
+            )?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Rotating secret...").green().bold().to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            match rotate_secret(client, &project_id, &id).await {
+                Ok(auth_app) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_with_secret(&auth_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        "Secret has been rotated. Store it now, it will not be shown again.",
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Redirect { command } => process_redirect(client, &project_id, command).await,
+        Commands::Scopes { command } => process_scopes(client, &project_id, command).await,
+        Commands::Users { app, command } => {
+            let auth_app_id = auth_app_id_or_current(app).await?;
+            process_users(client, &project_id, &auth_app_id, command).await
+        }
+        Commands::Try { .. } => unreachable!("handled before the project is resolved"),
+    }
+}
+
+/// `auth_app_id` is the app given on the command line, if any.
+pub async fn auth_app_id_or_current(auth_app_id: Option<String>) -> Result<String> {
+    if let Some(auth_app_id) = auth_app_id {
+        return Ok(auth_app_id);
+    }
+    match get_config().await?.current_auth_app {
+        Some(auth_app) => Ok(auth_app.id),
+        None => Err(MissingInput(
+            "No app selected. Pass the app id or pick one with smb oten use.".to_owned(),
+        )
+        .into()),
+    }
+}
+
+// Private functions
+
+/// Tables leave the secret out, so it gets a line of its own.
+fn print_with_secret(auth_app: &AuthApp) -> Result<()> {
+    print_one(auth_app)?;
+    if output_format() == OutputFormat::Table {
+        if let Some(secret) = &auth_app.secret {
+            println!("Secret: {secret}");
+        }
+    }
+    Ok(())
+}
+
+impl Tabular for AuthApp {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("ID", 36),
+            ("Name", 20),
+            ("Created at", 20),
+            ("Updated at", 20),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.created_at.date_naive().to_string(),
+            self.updated_at.date_naive().to_string(),
+        ]
+    }
+}
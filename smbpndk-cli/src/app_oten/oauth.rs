@@ -0,0 +1,358 @@
+use super::auth_app_id_or_current;
+use crate::cli::{
+    output::{output_format, OutputFormat},
+    prompt, CommandResult,
+};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use console::style;
+use log::debug;
+use reqwest::Url;
+use serde_json::{Map, Value};
+use smbpndk_model::app_auth::{GrantType, OauthToken, TokenRequest};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_oten::{authorize_url, request_token};
+use spinners::Spinner;
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Sender},
+    thread,
+    time::Duration,
+};
+
+const CALLBACK_PATH: &str = "/callback";
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Runs an OAuth grant against an app and shows the tokens it got.
+pub async fn process_try(
+    client: &SmbClient,
+    app: Option<String>,
+    secret_stdin: bool,
+    client_credentials: bool,
+    scope: Option<String>,
+    port: u16,
+) -> Result<CommandResult> {
+    let client_id = auth_app_id_or_current(app).await?;
+    let client_secret = prompt::secret(secret_stdin, "App secret", "--secret-stdin")?;
+
+    // The app authenticates with its own credentials, not with the user's session.
+    let mut oauth_client = client.clone();
+    oauth_client.set_token(None);
+
+    let token_request = if client_credentials {
+        TokenRequest {
+            grant_type: GrantType::ClientCredentials,
+            client_id,
+            client_secret,
+            code: None,
+            redirect_uri: None,
+            scope,
+        }
+    } else {
+        let redirect_uri = format!("http://localhost:{port}{CALLBACK_PATH}");
+        let code = authorize(
+            &oauth_client,
+            &client_id,
+            &redirect_uri,
+            scope.as_deref(),
+            port,
+        )
+        .await?;
+        TokenRequest {
+            grant_type: GrantType::AuthorizationCode,
+            client_id,
+            client_secret,
+            code: Some(code),
+            redirect_uri: Some(redirect_uri),
+            scope: None,
+        }
+    };
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Requesting a token...").green().bold().to_string(),
+    );
+    match request_token(&oauth_client, &token_request).await {
+        Ok(token) => {
+            spinner.stop_and_persist("✅", "Done.".to_owned());
+            let inspection = inspect(&token, Utc::now())?;
+            if output_format() == OutputFormat::Table {
+                print_inspection(&inspection)?;
+            }
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                "Got a token. Claims are decoded without checking the signature.",
+            )
+            .with_data(inspection))
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(e.into())
+        }
+    }
+}
+
+// Private functions
+
+/// Sends the user to the authorize page and waits for the code on the callback listener.
+async fn authorize(
+    client: &SmbClient,
+    client_id: &str,
+    redirect_uri: &str,
+    scope: Option<&str>,
+    port: u16,
+) -> Result<String> {
+    let state = random_state()?;
+    let url = authorize_url(client, client_id, redirect_uri, scope, &state)?;
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Could not listen on port {port}. Pick another with --port."))?;
+
+    eprintln!("Open this page to authorize the app:\n{url}");
+    if prompt::is_interactive() {
+        if let Err(e) = open::that(url.as_str()) {
+            debug!("Failed to open a browser: {e}");
+        }
+    }
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Waiting for the authorization...")
+            .green()
+            .bold()
+            .to_string(),
+    );
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || listen_for_code(listener, &state, tx));
+
+    match tokio::task::spawn_blocking(move || rx.recv_timeout(CALLBACK_TIMEOUT)).await? {
+        Ok(Ok(code)) => {
+            spinner.stop_and_persist("✅", "Authorized.".to_owned());
+            Ok(code)
+        }
+        Ok(Err(e)) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(e)
+        }
+        Err(_) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(anyhow!("Timed out waiting for the authorization."))
+        }
+    }
+}
+
+/// Serves the callback until one arrives, then sends its outcome to `tx`.
+fn listen_for_code(listener: TcpListener, state: &str, tx: Sender<Result<String>>) {
+    for stream in listener.incoming().flatten() {
+        if let Some(outcome) = handle_callback(stream, state) {
+            let _ = tx.send(outcome);
+            return;
+        }
+    }
+}
+
+/// Returns `None` for requests that are not the callback, like the favicon.
+fn handle_callback(mut stream: TcpStream, state: &str) -> Option<Result<String>> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    // Read the headers so the browser is not cut off mid request.
+    let mut line = String::new();
+    while reader.read_line(&mut line).ok()? > 2 {
+        line.clear();
+    }
+    debug!("Request: {:#?}", request_line);
+
+    let target = request_line.split_whitespace().nth(1)?;
+    let url = Url::parse(&format!("http://localhost{target}")).ok()?;
+    if url.path() != CALLBACK_PATH {
+        respond(&mut stream, "404 Not Found", "Not found.");
+        return None;
+    }
+
+    let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+    let outcome = if let Some(error) = params.get("error") {
+        let description = params
+            .get("error_description")
+            .map(|description| format!(": {description}"))
+            .unwrap_or_default();
+        Err(anyhow!(
+            "The authorization failed with {error}{description}"
+        ))
+    } else if params.get("state").map(String::as_str) != Some(state) {
+        Err(anyhow!("The callback state does not match. Try again."))
+    } else {
+        match params.get("code") {
+            Some(code) => Ok(code.clone()),
+            None => Err(anyhow!("The callback has no code.")),
+        }
+    };
+
+    match &outcome {
+        Ok(_) => respond(
+            &mut stream,
+            "200 OK",
+            "Authorized! Back to the terminal to see your tokens.",
+        ),
+        Err(_) => respond(
+            &mut stream,
+            "400 Bad Request",
+            "The authorization failed. Back to the terminal for details.",
+        ),
+    }
+    Some(outcome)
+}
+
+fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html>
+<html lang='en'>
+<head>
+    <meta charset='utf-8'>
+    <title>SmbPndk</title>
+</head>
+<body>
+    <p>{message}</p>
+</body>
+</html>"
+    );
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+fn random_state() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| anyhow!("Failed to generate a state: {e}"))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// The token response plus the decoded claims and when the access token expires.
+fn inspect(token: &OauthToken, now: DateTime<Utc>) -> Result<Value> {
+    let claims = decode_claims(&token.access_token);
+    let expires_at = claims
+        .as_ref()
+        .and_then(|claims| claims.get("exp"))
+        .and_then(Value::as_i64)
+        .and_then(|exp| Utc.timestamp_opt(exp, 0).single())
+        .or_else(|| {
+            token
+                .expires_in
+                .and_then(|seconds| i64::try_from(seconds).ok())
+                .map(|seconds| now + ChronoDuration::seconds(seconds))
+        });
+
+    let mut inspection = serde_json::to_value(token)?;
+    inspection["claims"] = claims.map(Value::Object).unwrap_or(Value::Null);
+    if let Some(id_token) = &token.id_token {
+        inspection["id_token_claims"] = decode_claims(id_token)
+            .map(Value::Object)
+            .unwrap_or(Value::Null);
+    }
+    inspection["expires_at"] = match expires_at {
+        Some(expires_at) => Value::String(expires_at.to_rfc3339()),
+        None => Value::Null,
+    };
+    inspection["expires"] = match expires_at {
+        Some(expires_at) => Value::String(describe_expiry(expires_at, now)),
+        None => Value::Null,
+    };
+    Ok(inspection)
+}
+
+/// The payload of a JWT, or `None` for opaque tokens. The signature is not verified.
+fn decode_claims(token: &str) -> Option<Map<String, Value>> {
+    let parts: Vec<&str> = token.split('.').collect();
+    let [_, payload, _] = parts.as_slice() else {
+        return None;
+    };
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&payload).ok()
+}
+
+fn describe_expiry(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (expires_at - now).num_seconds();
+    if seconds > 0 {
+        format!("in {}", human_duration(seconds))
+    } else {
+        format!("expired {} ago", human_duration(-seconds))
+    }
+}
+
+fn human_duration(seconds: i64) -> String {
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{seconds}s"),
+        (0, 0, _) => format!("{minutes}m {}s", seconds % 60),
+        (0, _, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+fn print_inspection(inspection: &Value) -> Result<()> {
+    let fields = [
+        ("Token type", "token_type"),
+        ("Access token", "access_token"),
+        ("Refresh token", "refresh_token"),
+        ("ID token", "id_token"),
+        ("Scope", "scope"),
+        ("Expires at", "expires_at"),
+        ("Expires", "expires"),
+    ];
+    for (label, key) in fields {
+        if let Some(value) = inspection[key].as_str() {
+            println!("{:<14} {value}", format!("{label}:"));
+        }
+    }
+    for (label, key) in [("Claims", "claims"), ("ID token claims", "id_token_claims")] {
+        if inspection[key].is_object() {
+            println!("{label}:");
+            println!("{}", serde_json::to_string_pretty(&inspection[key])?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"42","exp":1700003600}"#);
+        let token = OauthToken {
+            access_token: format!("eyJhbGciOiJub25lIn0.{payload}.signature"),
+            token_type: "Bearer".to_owned(),
+            expires_in: Some(60),
+            refresh_token: None,
+            id_token: Some("opaque".to_owned()),
+            scope: None,
+        };
+        let now = Utc.timestamp_opt(1700000000, 0).unwrap();
+
+        let inspection = inspect(&token, now).unwrap();
+        assert_eq!(inspection["claims"]["sub"], "42");
+        assert_eq!(inspection["id_token_claims"], Value::Null);
+        assert_eq!(inspection["expires_at"], "2023-11-14T23:13:20+00:00");
+        assert_eq!(inspection["expires"], "in 1h 0m");
+
+        let opaque = OauthToken {
+            access_token: "opaque".to_owned(),
+            ..token
+        };
+        let inspection = inspect(&opaque, now).unwrap();
+        assert_eq!(inspection["claims"], Value::Null);
+        assert_eq!(inspection["expires"], "in 1m 0s");
+
+        let later = Utc.timestamp_opt(1700000090, 0).unwrap();
+        assert_eq!(describe_expiry(now, later), "expired 1m 30s ago");
+    }
+}
@@ -0,0 +1,286 @@
+use super::cli::{RedirectCommands, ScopesCommands};
+use crate::cli::{
+    output::{print_list, Tabular},
+    CommandResult,
+};
+use anyhow::Result;
+use console::style;
+use serde::Serialize;
+use smbpndk_model::app_auth::{AuthApp, AuthAppUpdate};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use smbpndk_networking_oten::{get_auth_app, update_auth_app};
+use spinners::Spinner;
+use std::{collections::BTreeMap, net::IpAddr};
+use url::{Host, Url};
+
+/// A row of `smb oten redirect list`.
+#[derive(Serialize)]
+struct RedirectUriRow {
+    redirect_uri: String,
+}
+
+impl Tabular for RedirectUriRow {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![("Redirect URI", 60)]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![self.redirect_uri.clone()]
+    }
+}
+
+pub async fn process_redirect(
+    client: &SmbClient,
+    project_id: &str,
+    commands: RedirectCommands,
+) -> Result<CommandResult> {
+    // Catch typos before anything is fetched.
+    if let RedirectCommands::Add { uris, .. } = &commands {
+        check_redirect_uris(uris)?;
+    }
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Loading...").green().bold().to_string(),
+    );
+    let result = match commands {
+        RedirectCommands::Add { id, uris } => {
+            update_list(client, project_id, &id, |app| {
+                let mut redirect_uris = app.redirect_uris.clone();
+                for uri in uris {
+                    if !redirect_uris.contains(&uri) {
+                        redirect_uris.push(uri);
+                    }
+                }
+                Ok(AuthAppUpdate {
+                    redirect_uris: Some(redirect_uris),
+                    ..Default::default()
+                })
+            })
+            .await
+        }
+        RedirectCommands::Remove { id, uris } => {
+            update_list(client, project_id, &id, |app| {
+                if let Some(uri) = uris.iter().find(|uri| !app.redirect_uris.contains(uri)) {
+                    return Err(SmbError::NotFound(format!(
+                        "{uri} is not a redirect URI of app {}.",
+                        app.id
+                    ))
+                    .into());
+                }
+                Ok(AuthAppUpdate {
+                    redirect_uris: Some(
+                        app.redirect_uris
+                            .iter()
+                            .filter(|uri| !uris.contains(uri))
+                            .cloned()
+                            .collect(),
+                    ),
+                    ..Default::default()
+                })
+            })
+            .await
+        }
+        RedirectCommands::List { id } => get_auth_app(client, project_id, &id)
+            .await
+            .map_err(Into::into),
+    };
+
+    match result {
+        Ok(auth_app) => {
+            spinner.stop_and_persist("✅", "Done.".to_owned());
+            let msg = if auth_app.redirect_uris.is_empty() {
+                format!("{} has no redirect URIs.", auth_app.name)
+            } else {
+                format!("Showing redirect URIs of {}.", auth_app.name)
+            };
+            let rows: Vec<RedirectUriRow> = auth_app
+                .redirect_uris
+                .into_iter()
+                .map(|redirect_uri| RedirectUriRow { redirect_uri })
+                .collect();
+            print_list(&rows)?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                msg,
+            ))
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(e)
+        }
+    }
+}
+
+pub async fn process_scopes(
+    client: &SmbClient,
+    project_id: &str,
+    commands: ScopesCommands,
+) -> Result<CommandResult> {
+    match commands {
+        ScopesCommands::Set { id, scopes, none } => {
+            let scopes = if none { vec![] } else { scopes };
+            check_scopes(&scopes)?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Updating scopes...").green().bold().to_string(),
+            );
+            let auth_app = AuthAppUpdate {
+                scopes: Some(scopes),
+                ..Default::default()
+            };
+            match update_auth_app(client, project_id, &id, auth_app).await {
+                Ok(auth_app) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    let msg = if auth_app.scopes.is_empty() {
+                        format!("{} may not ask for any scope.", auth_app.name)
+                    } else {
+                        format!(
+                            "{} may ask for: {}.",
+                            auth_app.name,
+                            auth_app.scopes.join(" ")
+                        )
+                    };
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    )
+                    .with_data(serde_json::json!({ "id": id, "scopes": auth_app.scopes })))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+    }
+}
+
+/// Fails with every invalid URI, so they can all be fixed in one go.
+pub fn check_redirect_uris(uris: &[String]) -> Result<(), SmbError> {
+    check(
+        "redirect_uris",
+        "Invalid redirect URI.",
+        uris,
+        validate_redirect_uri,
+    )
+}
+
+pub fn check_scopes(scopes: &[String]) -> Result<(), SmbError> {
+    check("scopes", "Invalid scope.", scopes, validate_scope)
+}
+
+// Private functions
+
+/// Fetches the app, lets `change` work out the update and sends it.
+async fn update_list(
+    client: &SmbClient,
+    project_id: &str,
+    id: &str,
+    change: impl FnOnce(&AuthApp) -> Result<AuthAppUpdate>,
+) -> Result<AuthApp> {
+    let auth_app = get_auth_app(client, project_id, id).await?;
+    let update = change(&auth_app)?;
+    Ok(update_auth_app(client, project_id, id, update).await?)
+}
+
+fn check(
+    field: &str,
+    message: &str,
+    values: &[String],
+    validate: impl Fn(&str) -> Result<(), String>,
+) -> Result<(), SmbError> {
+    let problems: Vec<String> = values
+        .iter()
+        .filter_map(|value| validate(value).err())
+        .collect();
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(SmbError::Validation {
+        message: message.to_owned(),
+        errors: BTreeMap::from([(field.to_owned(), problems)]),
+    })
+}
+
+/// Follows RFC 6749 section 3.1.2 and the native app rules of RFC 8252: absolute, no
+/// fragment, https except on loopback, and custom schemes in reverse domain notation.
+fn validate_redirect_uri(uri: &str) -> Result<(), String> {
+    let url = Url::parse(uri).map_err(|e| format!("{uri} is not an absolute URI ({e})"))?;
+    if url.fragment().is_some() {
+        return Err(format!("{uri} must not have a fragment"));
+    }
+    if uri.contains('*') {
+        return Err(format!("{uri} must not have wildcards"));
+    }
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" => match url.host() {
+            Some(Host::Domain("localhost")) => Ok(()),
+            Some(Host::Ipv4(ip)) if IpAddr::V4(ip).is_loopback() => Ok(()),
+            Some(Host::Ipv6(ip)) if IpAddr::V6(ip).is_loopback() => Ok(()),
+            _ => Err(format!("{uri} must use https, http is only for localhost")),
+        },
+        scheme if scheme.contains('.') => Ok(()),
+        _ => Err(format!(
+            "{uri} must use https or a reverse domain scheme like com.example.app"
+        )),
+    }
+}
+
+/// A scope token as in RFC 6749 section 3.3.
+fn validate_scope(scope: &str) -> Result<(), String> {
+    let allowed = |c: char| matches!(c, '\x21' | '\x23'..='\x5b' | '\x5d'..='\x7e');
+    if scope.is_empty() || !scope.chars().all(allowed) {
+        return Err(format!(
+            "{scope:?} must be printable ASCII without spaces, quotes or backslashes"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_redirect_uri() {
+        for uri in [
+            "https://example.com/callback",
+            "http://localhost:8809/callback",
+            "http://127.0.0.1/callback",
+            "http://[::1]/callback",
+            "com.example.app:/callback",
+        ] {
+            assert!(validate_redirect_uri(uri).is_ok(), "{uri}");
+        }
+        for uri in [
+            "/callback",
+            "example.com/callback",
+            "http://example.com/callback",
+            "https://example.com/callback#token",
+            "https://*.example.com/callback",
+            "javascript:alert(1)",
+        ] {
+            assert!(validate_redirect_uri(uri).is_err(), "{uri}");
+        }
+    }
+
+    #[test]
+    fn test_check_scopes() {
+        assert!(check_scopes(&["read".to_owned(), "users:write".to_owned()]).is_ok());
+        let Err(SmbError::Validation { errors, .. }) =
+            check_scopes(&["read write".to_owned(), "".to_owned(), "ok".to_owned()])
+        else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors["scopes"].len(), 2);
+    }
+}
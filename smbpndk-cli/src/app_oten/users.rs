@@ -0,0 +1,216 @@
+use super::cli::UsersCommands;
+use crate::cli::{
+    output::{print_list, print_one, Tabular},
+    prompt, CommandResult,
+};
+use anyhow::Result;
+use console::style;
+use serde_json::json;
+use smbpndk_model::app_auth::{AppUser, AppUserQuery};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_oten::{
+    delete_app_user, get_app_user, get_app_users, set_app_user_disabled,
+};
+use spinners::Spinner;
+
+pub async fn process_users(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    commands: UsersCommands,
+) -> Result<CommandResult> {
+    match commands {
+        UsersCommands::List {
+            email,
+            page,
+            per_page,
+            all,
+        } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            let query = AppUserQuery {
+                page,
+                per_page,
+                email,
+            };
+            let result = if all {
+                get_all_users(client, project_id, auth_app_id, query).await
+            } else {
+                get_page(client, project_id, auth_app_id, query).await
+            };
+            match result {
+                Ok((users, msg)) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    print_list(&users)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e)
+                }
+            }
+        }
+        UsersCommands::Show { id } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_app_user(client, project_id, auth_app_id, &id).await {
+                Ok(app_user) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let message = format!("Showing user {}.", &app_user.email);
+                    print_one(&app_user)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        message,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        UsersCommands::Disable { id } => {
+            set_disabled(client, project_id, auth_app_id, &id, true).await
+        }
+        UsersCommands::Enable { id } => {
+            set_disabled(client, project_id, auth_app_id, &id, false).await
+        }
+        UsersCommands::Delete { id, yes } => {
+            let confirmation = prompt::confirm(yes, &format!("Delete user {id}?"))?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Deleting user...").green().bold().to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            match delete_app_user(client, project_id, auth_app_id, &id).await {
+                Ok(_) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        "User has been deleted.",
+                    )
+                    .with_data(json!({ "id": id })))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+    }
+}
+
+impl Tabular for AppUser {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![("ID", 8), ("Email", 30), ("Status", 10), ("Created at", 20)]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.email.clone(),
+            if self.disabled { "disabled" } else { "active" }.to_owned(),
+            self.created_at.date_naive().to_string(),
+        ]
+    }
+}
+
+// Private functions
+
+/// One page of users and a message saying where in the list it is.
+async fn get_page(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    query: AppUserQuery,
+) -> Result<(Vec<AppUser>, String)> {
+    let page = get_app_users(client, project_id, auth_app_id, &query).await?;
+    let mut msg = format!(
+        "Showing page {} of {} ({} users).",
+        page.page,
+        page.pages(),
+        page.total
+    );
+    if !page.is_last() {
+        msg.push_str(&format!(" Next: --page {}.", page.page + 1));
+    }
+    Ok((page.items, msg))
+}
+
+async fn get_all_users(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    mut query: AppUserQuery,
+) -> Result<(Vec<AppUser>, String)> {
+    let mut users = Vec::new();
+    loop {
+        let page = get_app_users(client, project_id, auth_app_id, &query).await?;
+        let is_last = page.is_last() || page.items.is_empty();
+        users.extend(page.items);
+        if is_last {
+            break;
+        }
+        query.page += 1;
+    }
+    let msg = format!("Showing all {} users.", users.len());
+    Ok((users, msg))
+}
+
+async fn set_disabled(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    id: &str,
+    disabled: bool,
+) -> Result<CommandResult> {
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Updating user...").green().bold().to_string(),
+    );
+    match set_app_user_disabled(client, project_id, auth_app_id, id, disabled).await {
+        Ok(app_user) => {
+            spinner.stop_and_persist("✅", "Done.".to_owned());
+            let msg = if disabled {
+                format!(
+                    "{} has been disabled. Their tokens no longer work.",
+                    app_user.email
+                )
+            } else {
+                format!("{} can sign in again.", app_user.email)
+            };
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                msg,
+            )
+            .with_data(json!({ "id": app_user.id, "disabled": app_user.disabled })))
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(e.into())
+        }
+    }
+}
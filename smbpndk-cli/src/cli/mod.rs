@@ -1,11 +1,143 @@
-use crate::{account, project};
+pub mod output;
+pub mod prompt;
+
+use self::output::OutputFormat;
+use crate::{account, app_oten, deploy, fun_app, pkt_app, profile, project, rdb_app};
 use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::Value;
+use smbpndk_model::account::ErrorCode;
+use smbpndk_networking::error::SmbError;
 use spinners::Spinner;
+use std::{path::PathBuf, time::Duration};
+
+/// How a command ended. `main` exits with [`Status::exit_code`].
+///
+/// | Code | Status                                  |
+/// |------|-----------------------------------------|
+/// | 0    | Success                                 |
+/// | 1    | Failure not covered below               |
+/// | 2    | Invalid arguments                       |
+/// | 3    | Login required or session expired       |
+/// | 4    | Not found                               |
+/// | 5    | Validation failed or input is missing   |
+/// | 6    | Network error                           |
+/// | 7    | Cancelled by the user                   |
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Success,
+    Failure,
+    AuthRequired,
+    NotFound,
+    Validation,
+    Network,
+    Cancelled,
+}
+
+impl Status {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Status::Success => 0,
+            Status::Failure => 1,
+            Status::AuthRequired => 3,
+            Status::NotFound => 4,
+            Status::Validation => 5,
+            Status::Network => 6,
+            Status::Cancelled => 7,
+        }
+    }
+
+    /// The status for a command that failed with `error`.
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<SmbError>() {
+                return match error {
+                    SmbError::Unauthorized => Status::AuthRequired,
+                    SmbError::NotFound(_) => Status::NotFound,
+                    SmbError::Validation { .. } => Status::Validation,
+                    SmbError::Transport(_) => Status::Network,
+                    SmbError::Account {
+                        code: ErrorCode::EmailNotFound,
+                        ..
+                    } => Status::NotFound,
+                    _ => Status::Failure,
+                };
+            }
+            if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+                return if error.is_decode() {
+                    Status::Failure
+                } else {
+                    Status::Network
+                };
+            }
+            if cause.is::<prompt::Cancelled>() {
+                return Status::Cancelled;
+            }
+            if cause.is::<prompt::MissingInput>() {
+                return Status::Validation;
+            }
+        }
+        Status::Failure
+    }
+}
+
+/// The message to show for a command that failed with `error`, its contexts followed by their
+/// causes. Stops at the first API error, whose message already tells what its source was.
+pub fn error_message(error: &anyhow::Error) -> String {
+    let mut messages = Vec::new();
+    for cause in error.chain() {
+        messages.push(cause.to_string());
+        if cause.is::<SmbError>() || cause.is::<reqwest::Error>() {
+            break;
+        }
+    }
+    messages.join(" ")
+}
 
+/// The outcome of a command. `main` stops the spinner with `msg` and exits with `status`.
 pub struct CommandResult {
     pub spinner: Spinner,
-    pub symbol: String,
+    pub status: Status,
     pub msg: String,
+    /// Printed to stdout with `--output` other than `table`.
+    pub data: Option<Value>,
+}
+
+impl CommandResult {
+    pub fn success(spinner: Spinner, msg: impl Into<String>) -> Self {
+        Self::new(spinner, Status::Success, msg)
+    }
+
+    pub fn failure(spinner: Spinner, status: Status, msg: impl Into<String>) -> Self {
+        Self::new(spinner, status, msg)
+    }
+
+    pub fn cancelled(spinner: Spinner, msg: impl Into<String>) -> Self {
+        Self::new(spinner, Status::Cancelled, msg)
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self.status {
+            Status::Success => "✅",
+            Status::Cancelled => "✘",
+            _ => "😩",
+        }
+    }
+
+    fn new(spinner: Spinner, status: Status, msg: impl Into<String>) -> Self {
+        Self {
+            spinner,
+            status,
+            msg: msg.into(),
+            data: None,
+        }
+    }
 }
 
 #[derive(Parser)]
@@ -15,6 +147,36 @@ pub struct Cli {
     #[clap(short, long, global = true)]
     pub log_level: Option<String>,
 
+    /// API environment: production, staging or local
+    #[clap(
+        long,
+        global = true,
+        value_parser = ["production", "staging", "local"],
+        conflicts_with = "api_url"
+    )]
+    pub env: Option<String>,
+
+    /// Custom API URL, e.g. http://localhost:8088. Also read from SMB_API_URL.
+    #[clap(long, global = true)]
+    pub api_url: Option<String>,
+
+    /// Profile to use for this command. Also read from SMB_PROFILE.
+    #[clap(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Never prompt. Missing values are errors instead. Also read from SMB_NO_INPUT.
+    #[clap(
+        long,
+        global = true,
+        env = "SMB_NO_INPUT",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub no_input: bool,
+
+    /// Output format for data. Status messages always go to stderr.
+    #[clap(short, long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
     #[clap(subcommand)]
     pub command: Commands,
 }
@@ -32,29 +194,146 @@ pub enum Commands {
         #[clap(subcommand)]
         command: project::cli::Commands,
     },
-    /*
+
+    #[clap(about = "Manage profiles for multiple accounts and backends.")]
+    Profile {
+        #[clap(subcommand)]
+        command: profile::cli::Commands,
+    },
     #[clap(about = "Manage your Oten authentication app. Add, delete, edit. Need authentication.")]
     Oten {
+        /// Project Id. Defaults to the current project.
+        #[clap(long, global = true)]
+        project_id: Option<String>,
+
         #[clap(subcommand)]
         command: app_oten::cli::Commands,
     },
-     */
     // Function
-    /*
-    FunApp {
+    #[clap(
+        about = "Manage your function apps. Add, delete, edit and invoke. Need authentication."
+    )]
+    Fun {
+        /// Project Id. Defaults to the current project.
+        #[clap(long, global = true)]
+        project_id: Option<String>,
+
         #[clap(subcommand)]
         command: fun_app::cli::Commands,
-    }, */
+    },
+    #[clap(
+        about = "Package a directory and deploy it to a function app. Files matching .smbignore and .env files are left out. Need authentication."
+    )]
+    Deploy {
+        /// Function app to deploy to. Asks when not given.
+        app: Option<String>,
+        /// Directory to deploy
+        #[clap(long, default_value = ".")]
+        path: PathBuf,
+        /// Project Id. Defaults to the current project.
+        #[clap(long)]
+        project_id: Option<String>,
+        /// Return once the deployment started instead of waiting for it
+        #[clap(long)]
+        no_wait: bool,
+        /// Seconds to wait for the deployment to be ready
+        #[clap(long, default_value_t = 600)]
+        timeout: u64,
+    },
+    #[clap(about = "Show the logs of a function app. Need authentication.")]
+    Logs {
+        /// Function app. Asks when not given.
+        app: Option<String>,
+        /// Keep printing new lines as they are logged
+        #[clap(short, long)]
+        follow: bool,
+        /// Only lines from this long ago, e.g. 30s, 10m, 2h or 1d
+        #[clap(long, value_parser = fun_app::logs::parse_since)]
+        since: Option<Duration>,
+        /// Only lines containing this text
+        #[clap(long)]
+        filter: Option<String>,
+        /// Project Id. Defaults to the current project.
+        #[clap(long)]
+        project_id: Option<String>,
+    },
+    #[clap(
+        about = "Show the deployments of a function app and their build logs. Need authentication."
+    )]
+    Deployments {
+        /// Project Id. Defaults to the current project.
+        #[clap(long, global = true)]
+        project_id: Option<String>,
+        /// Function app. Asks when not given.
+        #[clap(long, global = true)]
+        app: Option<String>,
+
+        #[clap(subcommand)]
+        command: deploy::cli::Commands,
+    },
+    #[clap(
+        about = "Make an earlier deployment of a function app live again. Need authentication."
+    )]
+    Rollback {
+        /// Deployment to go back to. Defaults to the last ready one before the live one.
+        id: Option<String>,
+        /// Function app. Asks when not given.
+        #[clap(long)]
+        app: Option<String>,
+        /// Project Id. Defaults to the current project.
+        #[clap(long)]
+        project_id: Option<String>,
+        /// Roll back without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
     // Package
-    /*
-    PktApp {
+    #[clap(
+        about = "Publish packages to the private registry of a project and yank them. Need authentication."
+    )]
+    Pkg {
+        /// Project Id. Defaults to the current project.
+        #[clap(long, global = true)]
+        project_id: Option<String>,
+
         #[clap(subcommand)]
         command: pkt_app::cli::Commands,
-    }, */
+    },
     // Relational database
-    /*
-    RdbApp {
+    #[clap(about = "Manage your databases and their credentials. Need authentication.")]
+    Db {
+        /// Project Id. Defaults to the current project.
+        #[clap(long, global = true)]
+        project_id: Option<String>,
+
         #[clap(subcommand)]
         command: rdb_app::cli::Commands,
-    }, */
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_error_message() {
+        let error = anyhow::Error::new(SmbError::from_parts(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            None,
+            r#"{"message": "Mail server down."}"#,
+        ))
+        .context("Failed to logout.");
+        assert_eq!(
+            error_message(&error),
+            "Failed to logout. Request failed with status 500: Mail server down."
+        );
+
+        let error = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("Failed to read seed.sql.");
+        assert_eq!(
+            error_message(&error),
+            "Failed to read seed.sql. entity not found"
+        );
+    }
 }
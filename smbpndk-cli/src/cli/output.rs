@@ -0,0 +1,190 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    io::{self, Write},
+    sync::OnceLock,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+    Jsonl,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Print data as `format` for the rest of this process. Set by `--output`.
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+pub fn output_format() -> OutputFormat {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Structs that can be printed as a row of a table.
+pub trait Tabular {
+    /// Column headers and their widths.
+    fn columns() -> Vec<(&'static str, usize)>;
+    fn row(&self) -> Vec<String>;
+}
+
+/// Prints `items` to stdout in the format chosen with `--output`.
+///
+/// Stdout only ever gets data, spinners and status messages go to stderr.
+pub fn print_list<T: Serialize + Tabular>(items: &[T]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match output_format() {
+        OutputFormat::Table => write_table(&mut out, items)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, items)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut out, items)?,
+        OutputFormat::Csv => write_csv(&mut out, items)?,
+        OutputFormat::Jsonl => {
+            for item in items {
+                serde_json::to_writer(&mut out, item)?;
+                writeln!(out)?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Like [`print_list`], but JSON and YAML print a single object instead of a list.
+pub fn print_one<T: Serialize + Tabular>(item: &T) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match output_format() {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, item)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut out, item)?,
+        _ => {
+            drop(out);
+            return print_list(std::slice::from_ref(item));
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Prints the payload of a command result. Tables have no use for it, the status message says it all.
+pub fn print_value(value: &Value) -> Result<()> {
+    let mut out = io::stdout().lock();
+    match output_format() {
+        OutputFormat::Table => return Ok(()),
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, value)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut out, value)?,
+        OutputFormat::Csv => write_csv_value(&mut out, value)?,
+        OutputFormat::Jsonl => {
+            serde_json::to_writer(&mut out, value)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+// Private functions
+
+fn write_table<T: Tabular>(out: &mut impl Write, items: &[T]) -> Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    let columns = T::columns();
+    let headers: Vec<String> = columns.iter().map(|(name, _)| name.to_string()).collect();
+    write_row(out, &columns, &headers)?;
+    for item in items {
+        write_row(out, &columns, &item.row())?;
+    }
+    Ok(())
+}
+
+fn write_row(out: &mut impl Write, columns: &[(&str, usize)], cells: &[String]) -> Result<()> {
+    let line = columns
+        .iter()
+        .zip(cells)
+        .map(|((_, width), cell)| format!("{cell: <width$}"))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    writeln!(out, "{line}")?;
+    Ok(())
+}
+
+fn write_csv<T: Serialize>(out: &mut impl Write, items: &[T]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    for item in items {
+        writer.serialize(item)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A flat object becomes a header and a row, anything else a single cell.
+fn write_csv_value(out: &mut impl Write, value: &Value) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    match value {
+        Value::Object(object) => {
+            writer.write_record(object.keys())?;
+            writer.write_record(object.values().map(|value| match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            }))?;
+        }
+        value => writer.write_record([value.to_string()])?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        id: i32,
+        name: String,
+    }
+
+    impl Tabular for Row {
+        fn columns() -> Vec<(&'static str, usize)> {
+            vec![("ID", 5), ("Name", 10)]
+        }
+
+        fn row(&self) -> Vec<String> {
+            vec![self.id.to_string(), self.name.clone()]
+        }
+    }
+
+    #[test]
+    fn test_table_and_csv() {
+        let rows = vec![Row {
+            id: 1,
+            name: "test".to_owned(),
+        }];
+
+        let mut table = vec![];
+        write_table(&mut table, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(table).unwrap(),
+            "ID    | Name      \n1     | test      \n"
+        );
+
+        let mut csv = vec![];
+        write_csv(&mut csv, &rows).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "id,name\n1,test\n");
+    }
+}
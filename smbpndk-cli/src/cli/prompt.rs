@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use console::Term;
+use dialoguer::{theme::ColorfulTheme, Confirm, FuzzySelect, Input, Password, Select};
+use smbpndk_utils::email_validation;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::{BufRead, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// The user backed out of a prompt.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancelled.")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// A value we would have prompted for is missing in non-interactive mode.
+#[derive(Debug)]
+pub struct MissingInput(pub String);
+
+impl Display for MissingInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for MissingInput {}
+
+/// Never prompt for the rest of this process. Set by `--no-input`.
+pub fn set_no_input(no_input: bool) {
+    NO_INPUT.store(no_input, Ordering::Relaxed);
+}
+
+/// We only prompt when `--no-input` is not set and there is a terminal to prompt on.
+pub fn is_interactive() -> bool {
+    !NO_INPUT.load(Ordering::Relaxed) && std::io::stdin().is_terminal() && Term::stderr().is_term()
+}
+
+/// Returns `value` when given on the command line, otherwise asks for it.
+pub fn text(value: Option<String>, prompt: &str, flag: &str) -> Result<String> {
+    if let Some(value) = value {
+        return Ok(value);
+    }
+    if !is_interactive() {
+        return Err(missing(flag));
+    }
+    let value = Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .interact_text()?;
+    Ok(value)
+}
+
+/// Asks for a value, starting from `initial` so it can be edited in place.
+pub fn edit(prompt: &str, initial: &str) -> Result<String> {
+    let value = Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .with_initial_text(initial)
+        .interact_text()?;
+    Ok(value)
+}
+
+/// Like [`text`], but the value has to be an email address.
+pub fn email(value: Option<String>, prompt: &str, flag: &str) -> Result<String> {
+    if let Some(email) = value {
+        email_validation(&email).map_err(|e| anyhow!(e))?;
+        return Ok(email);
+    }
+    if !is_interactive() {
+        return Err(missing(flag));
+    }
+    let email = Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .validate_with(|email: &String| email_validation(email))
+        .interact_text()?;
+    Ok(email)
+}
+
+/// Reads the password from stdin with `--password-stdin`, otherwise asks for it.
+pub fn password(from_stdin: bool, prompt: &str) -> Result<String> {
+    secret(from_stdin, prompt, "--password-stdin")
+}
+
+/// Reads a hidden value from stdin when `flag` is given, otherwise asks for it.
+pub fn secret(from_stdin: bool, prompt: &str, flag: &str) -> Result<String> {
+    if from_stdin {
+        return read_secret_from_stdin();
+    }
+    if !is_interactive() {
+        return Err(missing(flag));
+    }
+    let secret = Password::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .interact()?;
+    Ok(secret)
+}
+
+/// Like [`password`], but validates the length and asks twice when prompting.
+pub fn new_password(from_stdin: bool, prompt: &str) -> Result<String> {
+    if from_stdin {
+        let password = read_secret_from_stdin()?;
+        validate_password(&password).map_err(|e| anyhow!(e))?;
+        return Ok(password);
+    }
+    if !is_interactive() {
+        return Err(missing("--password-stdin"));
+    }
+    let password = Password::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .validate_with(|input: &String| validate_password(input))
+        .with_confirmation("Confirm password", "Passwords do not match")
+        .interact()?;
+    Ok(password)
+}
+
+/// Returns true right away with `--yes`, otherwise asks.
+pub fn confirm(yes: bool, prompt: &str) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if !is_interactive() {
+        return Err(MissingInput(
+            "Confirmation required. Pass --yes to confirm without a prompt.".to_owned(),
+        )
+        .into());
+    }
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .interact()?;
+    Ok(confirmed)
+}
+
+/// Asks to pick one of `items`. `flag` is what to pass instead in non-interactive mode.
+pub fn select<T: Display + Clone>(items: &[T], flag: &str) -> Result<T> {
+    if !is_interactive() {
+        return Err(missing(flag));
+    }
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .items(items)
+        .default(0)
+        .interact_on_opt(&Term::stderr())?;
+    match selection {
+        Some(index) => Ok(items[index].clone()),
+        None => Err(Cancelled.into()),
+    }
+}
+
+/// Like [`select`], but the list can be narrowed down by typing.
+pub fn fuzzy_select<T: Display + Clone>(items: &[T], prompt: &str, flag: &str) -> Result<T> {
+    if !is_interactive() {
+        return Err(missing(flag));
+    }
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .default(0)
+        .interact_on_opt(&Term::stderr())?;
+    match selection {
+        Some(index) => Ok(items[index].clone()),
+        None => Err(Cancelled.into()),
+    }
+}
+
+/// The error for a value that would have been asked for, if asking was allowed.
+pub fn missing(flag: &str) -> anyhow::Error {
+    MissingInput(format!(
+        "Missing {flag}. It is required when running without input."
+    ))
+    .into()
+}
+
+// Private functions
+
+fn validate_password(input: &str) -> Result<(), &'static str> {
+    if input.len() >= 6 {
+        Ok(())
+    } else {
+        Err("Password must be at least 6 characters")
+    }
+}
+
+fn read_secret_from_stdin() -> Result<String> {
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    let secret = line.trim_end_matches(['\r', '\n']);
+    if secret.is_empty() {
+        return Err(anyhow!("Nothing was given on stdin."));
+    }
+    Ok(secret.to_owned())
+}
@@ -0,0 +1,162 @@
+use anyhow::{anyhow, Context, Result};
+use flate2::{write::GzEncoder, Compression};
+use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
+use std::{ffi::OsStr, fs, path::Path};
+
+/// Like `.gitignore`, lists what `smb deploy` and `smb pkg publish` leave out.
+pub const IGNORE_FILE: &str = ".smbignore";
+
+/// A directory packaged for upload.
+pub struct Archive {
+    /// Gzipped tarball of the files.
+    pub bytes: Vec<u8>,
+    /// SHA-256 over the paths and contents of the files, the same for the same files.
+    pub content_hash: String,
+    pub files: usize,
+}
+
+/// Packs every file under `dir` that `.smbignore` does not exclude. `.git` and `.env` files
+/// are always left out, so secrets do not end up in an upload.
+///
+/// Files are added in path order with zeroed timestamps and owners, so packing the same files
+/// twice gives the same content hash.
+pub fn package(dir: &Path) -> Result<Archive> {
+    package_excluding(dir, |_| false)
+}
+
+/// Like [`package`], but also leaves out the files `is_excluded` is true for, whatever
+/// `.smbignore` says.
+pub fn package_excluding(dir: &Path, is_excluded: impl Fn(&Path) -> bool) -> Result<Archive> {
+    if !dir.is_dir() {
+        return Err(anyhow!("{} is not a directory.", dir.display()));
+    }
+
+    let mut paths = Vec::new();
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(|entry| !is_left_out(entry.file_name()))
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|t| t.is_file()) {
+            let path = entry.into_path();
+            if !is_excluded(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    let mut hasher = Sha256::new();
+    let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for path in &paths {
+        let name = path.strip_prefix(dir)?;
+        // Forward slashes, whatever the platform.
+        let name = name
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = fs::read(path).with_context(|| format!("Failed to read {name}."))?;
+
+        hasher.update(format!(
+            "{name}\0{}\n",
+            hex::encode(Sha256::digest(&contents))
+        ));
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(if is_executable(path)? { 0o755 } else { 0o644 });
+        header.set_mtime(0);
+        header.set_cksum();
+        tar.append_data(&mut header, &name, contents.as_slice())?;
+    }
+
+    Ok(Archive {
+        bytes: tar.into_inner()?.finish()?,
+        content_hash: hex::encode(hasher.finalize()),
+        files: paths.len(),
+    })
+}
+
+// Private functions
+
+/// Matches `.git` and `.env` as well as `.env.local` and the like, but not `.envrc`.
+fn is_left_out(name: &OsStr) -> bool {
+    let name = name.to_string_lossy();
+    name == ".git" || name == ".env" || name.starts_with(".env.")
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(fs::metadata(path)?.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> Result<bool> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+
+    fn names(archive: &Archive) -> Vec<String> {
+        let mut tar = tar::Archive::new(GzDecoder::new(archive.bytes.as_slice()));
+        tar.entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_package() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/left-pad")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(
+            dir.path().join("src/index.js"),
+            "exports.handler = () => {}",
+        )
+        .unwrap();
+        fs::write(dir.path().join("node_modules/left-pad/index.js"), "").unwrap();
+        fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/main").unwrap();
+        fs::write(dir.path().join(".env"), "SECRET=1").unwrap();
+        fs::write(dir.path().join(".env.production"), "SECRET=2").unwrap();
+        fs::create_dir_all(dir.path().join("config")).unwrap();
+        fs::write(dir.path().join("config/.env.local"), "SECRET=3").unwrap();
+        fs::write(dir.path().join("config/environment.js"), "").unwrap();
+        fs::write(dir.path().join(".envrc"), "use nix").unwrap();
+        fs::write(dir.path().join(IGNORE_FILE), "node_modules/\n*.log\n").unwrap();
+        fs::write(dir.path().join("debug.log"), "").unwrap();
+
+        let archive = package(dir.path()).unwrap();
+        assert_eq!(
+            names(&archive),
+            [
+                ".envrc",
+                ".smbignore",
+                "config/environment.js",
+                "src/index.js"
+            ],
+            "secrets are not packaged"
+        );
+        assert_eq!(archive.files, 4);
+
+        // Same files, same hash. Changed files, new hash.
+        assert_eq!(
+            package(dir.path()).unwrap().content_hash,
+            archive.content_hash
+        );
+        fs::write(dir.path().join("src/index.js"), "exports.handler = () => 1").unwrap();
+        assert_ne!(
+            package(dir.path()).unwrap().content_hash,
+            archive.content_hash
+        );
+    }
+}
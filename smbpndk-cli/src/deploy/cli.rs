@@ -0,0 +1,13 @@
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[clap(about = "List deployments of a function app, newest first.")]
+    List {},
+
+    #[clap(about = "Show a deployment and its build logs.")]
+    Show {
+        /// Deployment id
+        id: String,
+    },
+}
@@ -0,0 +1,185 @@
+use super::{cli::Commands, fun_app_or_select};
+use crate::{
+    cli::{
+        output::{output_format, print_list, print_one, OutputFormat, Tabular},
+        prompt, CommandResult,
+    },
+    project::project_id_or_current,
+};
+use anyhow::{anyhow, Result};
+use console::style;
+use smbpndk_model::deployment::{Deployment, DeploymentStatus};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_fun::{
+    get_deployment, get_deployment_logs, get_deployments, promote_deployment,
+};
+use spinners::Spinner;
+
+pub async fn process_deployments(
+    client: &SmbClient,
+    project_id: Option<String>,
+    app: Option<String>,
+    commands: Commands,
+) -> Result<CommandResult> {
+    let project_id = project_id_or_current(project_id).await?;
+    let app = fun_app_or_select(client, &project_id, app, "--app").await?;
+
+    match commands {
+        Commands::List {} => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_deployments(client, &project_id, &app).await {
+                Ok(deployments) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let msg = if deployments.is_empty() {
+                        format!("{app} has not been deployed yet.")
+                    } else {
+                        format!("Showing deployments of {app}.")
+                    };
+                    print_list(&deployments)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Show { id } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            let result = async {
+                let deployment = get_deployment(client, &project_id, &app, &id).await?;
+                let logs = get_deployment_logs(client, &project_id, &app, &id).await?;
+                Ok::<_, anyhow::Error>((deployment, logs))
+            }
+            .await;
+            match result {
+                Ok((deployment, logs)) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let msg = format!("Showing deployment {id} of {app}.");
+                    let spinner = Spinner::new(
+                        spinners::Spinners::SimpleDotsScrolling,
+                        style("Loading...").green().bold().to_string(),
+                    );
+                    if output_format() == OutputFormat::Table {
+                        print_one(&deployment)?;
+                        if let Some(message) = &deployment.message {
+                            println!("\n{message}");
+                        }
+                        println!("\n{logs}");
+                        return Ok(CommandResult::success(spinner, msg));
+                    }
+                    // Logs do not fit in a row, so every other format gets them as a field.
+                    let mut data = serde_json::to_value(&deployment)?;
+                    data["logs"] = logs.into();
+                    Ok(CommandResult::success(spinner, msg).with_data(data))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+/// Makes deployment `id` live again, or the last ready one before the live one.
+pub async fn process_rollback(
+    client: &SmbClient,
+    project_id: Option<String>,
+    app: Option<String>,
+    id: Option<String>,
+    yes: bool,
+) -> Result<CommandResult> {
+    let project_id = project_id_or_current(project_id).await?;
+    let app = fun_app_or_select(client, &project_id, app, "--app").await?;
+    let id = match id {
+        Some(id) => id,
+        None => previous_deployment(client, &project_id, &app).await?,
+    };
+
+    let confirmation = prompt::confirm(yes, &format!("Roll {app} back to deployment {id}?"))?;
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style(format!("Rolling {app} back..."))
+            .green()
+            .bold()
+            .to_string(),
+    );
+
+    if !confirmation {
+        return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+    }
+    match promote_deployment(client, &project_id, &app, &id).await {
+        Ok(deployment) => {
+            spinner.stop_and_persist("✅", "Done.".to_owned());
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                format!("{app} is live with deployment {}.", deployment.id),
+            )
+            .with_data(serde_json::to_value(&deployment)?))
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            Err(e.into())
+        }
+    }
+}
+
+impl Tabular for Deployment {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("ID", 6),
+            ("Status", 14),
+            ("Author", 24),
+            ("Created at", 17),
+            ("Hash", 12),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let status = if self.live {
+            format!("{} (live)", self.status)
+        } else {
+            self.status.to_string()
+        };
+        vec![
+            self.id.to_string(),
+            status,
+            self.author.clone(),
+            self.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            self.content_hash.chars().take(12).collect(),
+        ]
+    }
+}
+
+// Private functions
+
+/// The newest ready deployment older than the live one.
+async fn previous_deployment(client: &SmbClient, project_id: &str, app: &str) -> Result<String> {
+    let deployments = get_deployments(client, project_id, app).await?;
+    let live = deployments
+        .iter()
+        .position(|deployment| deployment.live)
+        .ok_or_else(|| anyhow!("{app} has no live deployment to roll back from."))?;
+    deployments[live + 1..]
+        .iter()
+        .find(|deployment| deployment.status == DeploymentStatus::Ready)
+        .map(|deployment| deployment.id.to_string())
+        .ok_or_else(|| anyhow!("{app} has no earlier ready deployment to roll back to."))
+}
@@ -0,0 +1,231 @@
+pub mod archive;
+pub mod cli;
+pub mod deployments;
+
+use self::archive::package;
+use crate::{
+    cli::{prompt, CommandResult, Status},
+    project::project_id_or_current,
+};
+use anyhow::Result;
+use console::style;
+use futures_util::{stream, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use smbpndk_model::deployment::{Deployment, DeploymentCreate, DeploymentStatus};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_fun::{
+    archive_exists, create_deployment, get_deployment, get_fun_apps, upload_archive,
+};
+use spinners::Spinner;
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Packages `path`, uploads it unless the same files were uploaded before, and deploys it to
+/// the function app `app`. Waits up to `timeout` for the deployment unless `no_wait`.
+pub async fn process_deploy(
+    client: &SmbClient,
+    project_id: Option<String>,
+    app: Option<String>,
+    path: &Path,
+    no_wait: bool,
+    timeout: Duration,
+) -> Result<CommandResult> {
+    let project_id = project_id_or_current(project_id).await?;
+    let app = fun_app_or_select(client, &project_id, app, "<APP>").await?;
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style(format!("Packaging {}...", path.display()))
+            .green()
+            .bold()
+            .to_string(),
+    );
+    let archive = match package(path) {
+        Ok(archive) => {
+            spinner.stop_and_persist(
+                "✅",
+                format!(
+                    "Packaged {} files ({}).",
+                    archive.files,
+                    indicatif::HumanBytes(archive.bytes.len() as u64)
+                ),
+            );
+            archive
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            return Err(e);
+        }
+    };
+
+    if archive_exists(client, &project_id, &app, &archive.content_hash).await? {
+        eprintln!("Nothing changed since the last upload, skipping it.");
+    } else {
+        upload(
+            client,
+            &project_id,
+            &app,
+            &archive.content_hash,
+            archive.bytes,
+        )
+        .await?;
+    }
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style(format!("Deploying {app}..."))
+            .green()
+            .bold()
+            .to_string(),
+    );
+    let deployment = DeploymentCreate {
+        content_hash: archive.content_hash,
+    };
+    let deployment = match create_deployment(client, &project_id, &app, deployment).await {
+        Ok(deployment) if no_wait => {
+            spinner.stop_and_persist("✅", "Started.".to_owned());
+            deployment
+        }
+        Ok(deployment) => match wait(client, &project_id, &app, deployment, timeout).await {
+            Ok(deployment) => {
+                spinner.stop_and_persist("✅", "Done.".to_owned());
+                deployment
+            }
+            Err(e) => {
+                spinner.stop_and_persist("😩", "Failed.".to_owned());
+                return Err(e);
+            }
+        },
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            return Err(e.into());
+        }
+    };
+
+    let data = serde_json::to_value(&deployment)?;
+    let spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Loading...").green().bold().to_string(),
+    );
+    let result = match deployment.status {
+        DeploymentStatus::Ready => CommandResult::success(
+            spinner,
+            format!("{app} is live with deployment {}.", deployment.id),
+        ),
+        DeploymentStatus::Failed => CommandResult::failure(
+            spinner,
+            Status::Failure,
+            format!(
+                "Deployment {} failed: {}",
+                deployment.id,
+                deployment
+                    .message
+                    .as_deref()
+                    .unwrap_or("no reason was given.")
+            ),
+        ),
+        status if no_wait => CommandResult::success(
+            spinner,
+            format!("Deployment {} is {status}.", deployment.id),
+        ),
+        status => CommandResult::failure(
+            spinner,
+            Status::Failure,
+            format!(
+                "Deployment {} is still {status} after {} seconds.",
+                deployment.id,
+                timeout.as_secs()
+            ),
+        ),
+    };
+    Ok(result.with_data(data))
+}
+
+/// `app` if given, otherwise asks which function app of the project to use. `flag` is how to
+/// give it on the command line.
+pub async fn fun_app_or_select(
+    client: &SmbClient,
+    project_id: &str,
+    app: Option<String>,
+    flag: &str,
+) -> Result<String> {
+    if let Some(app) = app {
+        return Ok(app);
+    }
+    if !prompt::is_interactive() {
+        return Err(prompt::missing(flag));
+    }
+    let names: Vec<String> = get_fun_apps(client, project_id)
+        .await?
+        .into_iter()
+        .map(|fun_app| fun_app.name)
+        .collect();
+    if names.is_empty() {
+        return Err(anyhow::anyhow!(
+            "The project has no function apps. Create one with smb fun new."
+        ));
+    }
+    prompt::fuzzy_select(&names, "Function app", flag)
+}
+
+// Private functions
+
+async fn upload(
+    client: &SmbClient,
+    project_id: &str,
+    app: &str,
+    content_hash: &str,
+    bytes: Vec<u8>,
+) -> Result<()> {
+    let length = bytes.len() as u64;
+    let progress = ProgressBar::new(length).with_style(
+        ProgressStyle::with_template(
+            "Uploading {bar:40.green} {bytes}/{total_bytes} ({bytes_per_sec})",
+        )?
+        .progress_chars("=> "),
+    );
+    let chunks: Vec<Vec<u8>> = bytes
+        .chunks(UPLOAD_CHUNK_SIZE)
+        .map(<[u8]>::to_vec)
+        .collect();
+    let body = {
+        let progress = progress.clone();
+        reqwest::Body::wrap_stream(stream::iter(chunks).map(move |chunk| {
+            progress.inc(chunk.len() as u64);
+            Ok::<_, std::io::Error>(chunk)
+        }))
+    };
+    match upload_archive(client, project_id, app, content_hash, length, body).await {
+        Ok(()) => {
+            progress.finish_and_clear();
+            eprintln!("Uploaded {}.", indicatif::HumanBytes(length));
+            Ok(())
+        }
+        Err(e) => {
+            progress.abandon();
+            Err(e.into())
+        }
+    }
+}
+
+/// Polls the deployment until it is ready, failed or `timeout` passed.
+async fn wait(
+    client: &SmbClient,
+    project_id: &str,
+    app: &str,
+    mut deployment: Deployment,
+    timeout: Duration,
+) -> Result<Deployment> {
+    let started = Instant::now();
+    let id = deployment.id.to_string();
+    while !deployment.status.is_finished() && started.elapsed() < timeout {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        deployment = get_deployment(client, project_id, app, &id).await?;
+    }
+    Ok(deployment)
+}
@@ -0,0 +1,60 @@
+use clap::Subcommand;
+
+/// Memory a function app can have, in MB.
+pub const MIN_MEMORY_MB: u32 = 128;
+pub const MAX_MEMORY_MB: u32 = 4096;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[clap(about = "Add new function app.")]
+    New {
+        /// App name, unique within the project
+        #[clap(short, long)]
+        name: Option<String>,
+        /// Runtime, e.g. nodejs18 or python3.11
+        #[clap(short, long)]
+        runtime: Option<String>,
+        /// Memory in MB, 128 to 4096
+        #[clap(short, long, default_value_t = 128, value_parser = clap::value_parser!(u32).range(MIN_MEMORY_MB as i64..=MAX_MEMORY_MB as i64))]
+        memory: u32,
+    },
+
+    #[clap(about = "List all function apps of the project.")]
+    List {},
+
+    #[clap(about = "Show detail of a function app.")]
+    Show {
+        /// App name
+        name: String,
+    },
+
+    #[clap(about = "Change the runtime or memory of a function app.")]
+    Edit {
+        /// App name
+        name: String,
+        /// New runtime
+        #[clap(short, long)]
+        runtime: Option<String>,
+        /// New memory in MB, 128 to 4096
+        #[clap(short, long, value_parser = clap::value_parser!(u32).range(MIN_MEMORY_MB as i64..=MAX_MEMORY_MB as i64))]
+        memory: Option<u32>,
+    },
+
+    #[clap(about = "Delete a function app.")]
+    Delete {
+        /// App name
+        name: String,
+        /// Delete without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Run a function app and show its response and how long it took.")]
+    Invoke {
+        /// App name
+        name: String,
+        /// JSON payload. Use @file to read it from a file, or @- for stdin.
+        #[clap(short, long)]
+        data: Option<String>,
+    },
+}
@@ -0,0 +1,281 @@
+use crate::{
+    cli::{
+        output::{output_format, OutputFormat},
+        CommandResult,
+    },
+    deploy::fun_app_or_select,
+    project::project_id_or_current,
+};
+use anyhow::Result;
+use chrono::Utc;
+use console::style;
+use futures_util::StreamExt;
+use log::debug;
+use smbpndk_model::app_fun::{LogLine, LogQuery};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use smbpndk_networking_fun::get_fun_app_logs;
+use spinners::Spinner;
+use std::{collections::BTreeMap, io::Write, time::Duration};
+
+/// Reconnect delay until the server sets one with `retry:`.
+const DEFAULT_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(30);
+/// Failed reconnects in a row before giving up.
+const MAX_RECONNECTS: u32 = 10;
+
+/// Prints the log lines of function app `app`. With `follow` it keeps the stream open and
+/// reconnects where it left off when the connection drops.
+pub async fn process_logs(
+    client: &SmbClient,
+    project_id: Option<String>,
+    app: Option<String>,
+    follow: bool,
+    since: Option<Duration>,
+    filter: Option<String>,
+) -> Result<CommandResult> {
+    let format = output_format();
+    if !matches!(format, OutputFormat::Table | OutputFormat::Jsonl) {
+        return Err(SmbError::Validation {
+            message: "Logs are streamed, so they are only printed as text or JSON lines."
+                .to_owned(),
+            errors: BTreeMap::from([(
+                "output".to_owned(),
+                vec!["must be table or jsonl".to_owned()],
+            )]),
+        }
+        .into());
+    }
+    let project_id = project_id_or_current(project_id).await?;
+    let name = fun_app_or_select(client, &project_id, app, "<APP>").await?;
+
+    let query = LogQuery {
+        since: since
+            .map(|since| chrono::Duration::from_std(since).map(|since| Utc::now() - since))
+            .transpose()?,
+        filter,
+        follow,
+    };
+    let mut stream = EventStream::default();
+    let mut failures = 0;
+    loop {
+        match read_stream(client, &project_id, &name, &query, &mut stream, format).await {
+            // Without follow the server closes the stream once it sent everything.
+            Ok(_) if !follow => break,
+            Ok(received) => {
+                if received > 0 {
+                    failures = 0;
+                }
+            }
+            Err(e) if follow && e.is_transient() => {
+                debug!("Log stream failed: {e}");
+                if stream.received > 0 {
+                    failures = 0;
+                }
+                failures += 1;
+                if failures > MAX_RECONNECTS {
+                    return Err(e.into());
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+        let delay = (stream.retry * 2u32.pow(failures.saturating_sub(1))).min(MAX_RETRY);
+        eprintln!(
+            "{} Connection lost, reconnecting in {} s...",
+            style("!").for_stderr().yellow(),
+            delay.as_secs()
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    Ok(CommandResult::success(
+        Spinner::new(
+            spinners::Spinners::SimpleDotsScrolling,
+            style("Loading...").green().bold().to_string(),
+        ),
+        format!("End of the logs of {name}."),
+    ))
+}
+
+/// Parses durations like `30s`, `10m`, `2h` or `1d`.
+pub fn parse_since(since: &str) -> Result<Duration, String> {
+    let split = since
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(since.len());
+    let (amount, unit) = since.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("{since} is not a duration like 30s, 10m, 2h or 1d"))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("{since} needs a unit: s, m, h or d")),
+    };
+    Ok(Duration::from_secs(amount * seconds))
+}
+
+// Private functions
+
+/// Reads one connection until the server closes it and returns how many lines it printed.
+async fn read_stream(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    query: &LogQuery,
+    stream: &mut EventStream,
+    format: OutputFormat,
+) -> Result<usize, SmbError> {
+    stream.reset();
+    let response = get_fun_app_logs(
+        client,
+        project_id,
+        name,
+        query,
+        stream.last_event_id.as_deref(),
+    )
+    .await?;
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        for data in stream.push(&chunk?) {
+            match serde_json::from_str::<LogLine>(&data) {
+                Ok(line) => print_line(&line, format),
+                // Keep-alives and events this version does not know.
+                Err(e) => debug!("Skipped event {data:?}: {e}"),
+            }
+            stream.received += 1;
+        }
+    }
+    Ok(stream.received)
+}
+
+fn print_line(line: &LogLine, format: OutputFormat) {
+    let mut out = std::io::stdout().lock();
+    let _ = if format == OutputFormat::Jsonl {
+        match serde_json::to_string(line) {
+            Ok(json) => writeln!(out, "{json}"),
+            Err(_) => return,
+        }
+    } else {
+        let level = format!("{:<5}", line.level.to_uppercase());
+        let level = match line.level.as_str() {
+            "error" | "fatal" => style(level).red(),
+            "warn" | "warning" => style(level).yellow(),
+            "info" => style(level).green(),
+            _ => style(level).dim(),
+        };
+        writeln!(
+            out,
+            "{} {} {}",
+            style(line.timestamp.format("%Y-%m-%d %H:%M:%S")).dim(),
+            level,
+            line.message
+        )
+    };
+    let _ = out.flush();
+}
+
+/// Splits a `text/event-stream` body into the data of its events, remembering the last event
+/// id and the reconnect delay across connections.
+struct EventStream {
+    buffer: Vec<u8>,
+    data: Vec<String>,
+    /// Id of the event being read, it only counts once the event is complete.
+    pending_id: Option<String>,
+    last_event_id: Option<String>,
+    retry: Duration,
+    /// Events of the current connection.
+    received: usize,
+}
+
+impl Default for EventStream {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            data: Vec::new(),
+            pending_id: None,
+            last_event_id: None,
+            retry: DEFAULT_RETRY,
+            received: 0,
+        }
+    }
+}
+
+impl EventStream {
+    /// Starts a new connection. Whatever is left of an event the last one broke off in is
+    /// dropped, the server sends that event again.
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.data.clear();
+        self.pending_id = None;
+        self.received = 0;
+    }
+
+    /// Adds a chunk of the body and returns the data of every event it completed.
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if let Some(id) = self.pending_id.take() {
+                    self.last_event_id = Some(id);
+                }
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => self.data.push(value.to_owned()),
+                "id" if !value.contains('\0') => self.pending_id = Some(value.to_owned()),
+                "retry" => {
+                    if let Ok(ms) = value.parse() {
+                        self.retry = Duration::from_millis(ms);
+                    }
+                }
+                // Comments start with a colon, `event` only names the type.
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_stream() {
+        let mut stream = EventStream::default();
+        assert!(stream
+            .push(b": keep-alive\n\nid: 1\ndata: {\"a\"")
+            .is_empty());
+        let events = stream.push(b":1}\r\n\r\nretry: 5000\nid: 2\ndata: x\ndata: y\n\n");
+        assert_eq!(events, ["{\"a\":1}", "x\ny"]);
+        assert_eq!(stream.last_event_id.as_deref(), Some("2"));
+        assert_eq!(stream.retry, Duration::from_secs(5));
+
+        // A connection that breaks off mid-event neither resumes after it nor garbles the next.
+        assert!(stream.push(b"id: 3\ndata: {\"b\"").is_empty());
+        assert_eq!(stream.last_event_id.as_deref(), Some("2"));
+        stream.reset();
+        assert_eq!(stream.push(b"id: 3\ndata: z\n\n"), ["z"]);
+        assert_eq!(stream.last_event_id.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_since("1d"), Ok(Duration::from_secs(86400)));
+        assert!(parse_since("10").is_err());
+        assert!(parse_since("m").is_err());
+        assert!(parse_since("10 minutes").is_err());
+    }
+}
@@ -0,0 +1,335 @@
+pub mod cli;
+pub mod logs;
+
+use self::cli::{Commands, MAX_MEMORY_MB, MIN_MEMORY_MB};
+use crate::{
+    cli::{
+        output::{output_format, print_list, print_one, OutputFormat, Tabular},
+        prompt::{self, MissingInput},
+        CommandResult,
+    },
+    project::project_id_or_current,
+};
+use anyhow::{Context, Result};
+use console::style;
+use serde_json::{json, Value};
+use smbpndk_model::app_fun::{FunApp, FunAppCreate, FunAppUpdate};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use smbpndk_networking_fun::{
+    create_fun_app, delete_fun_app, get_fun_app, get_fun_apps, invoke_fun_app, update_fun_app,
+};
+use spinners::Spinner;
+use std::{collections::BTreeMap, io::Read, time::Instant};
+
+/// `project_id` is the `--project-id` given on the command line, if any.
+pub async fn process_fun(
+    client: &SmbClient,
+    project_id: Option<String>,
+    commands: Commands,
+) -> Result<CommandResult> {
+    let project_id = project_id_or_current(project_id).await?;
+
+    match commands {
+        Commands::New {
+            name,
+            runtime,
+            memory,
+        } => {
+            let name = prompt::text(name, "App name", "--name")?;
+            let runtime = prompt::text(runtime, "Runtime", "--runtime")?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Creating a function app...")
+                    .green()
+                    .bold()
+                    .to_string(),
+            );
+            let fun_app = FunAppCreate {
+                name,
+                runtime,
+                memory_mb: memory,
+            };
+            match create_fun_app(client, &project_id, fun_app).await {
+                Ok(fun_app) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&fun_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{} has been created.", fun_app.name),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::List {} => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_fun_apps(client, &project_id).await {
+                Ok(fun_apps) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let msg = if fun_apps.is_empty() {
+                        "No function apps found."
+                    } else {
+                        "Showing all function apps."
+                    };
+                    print_list(&fun_apps)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Show { name } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_fun_app(client, &project_id, &name).await {
+                Ok(fun_app) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    print_one(&fun_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("Showing function app {name}."),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Edit {
+            name,
+            runtime,
+            memory,
+        } => {
+            let update = if runtime.is_some() || memory.is_some() {
+                FunAppUpdate {
+                    runtime,
+                    memory_mb: memory,
+                }
+            } else if prompt::is_interactive() {
+                let fun_app = get_fun_app(client, &project_id, &name).await?;
+                let runtime = prompt::edit("Runtime", &fun_app.runtime)?;
+                let memory_mb = parse_memory(&prompt::edit(
+                    "Memory (MB)",
+                    &fun_app.memory_mb.to_string(),
+                )?)?;
+                FunAppUpdate {
+                    runtime: (runtime != fun_app.runtime).then_some(runtime),
+                    memory_mb: (memory_mb != fun_app.memory_mb).then_some(memory_mb),
+                }
+            } else {
+                return Err(MissingInput(
+                    "Nothing to change. Pass --runtime or --memory.".to_owned(),
+                )
+                .into());
+            };
+
+            if update.runtime.is_none() && update.memory_mb.is_none() {
+                return Ok(CommandResult::success(
+                    Spinner::new(
+                        spinners::Spinners::SimpleDotsScrolling,
+                        style("Loading...").green().bold().to_string(),
+                    ),
+                    "Nothing to change.",
+                ));
+            }
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Updating function app...").green().bold().to_string(),
+            );
+            match update_fun_app(client, &project_id, &name, update).await {
+                Ok(fun_app) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&fun_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{} has been updated.", fun_app.name),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Delete { name, yes } => {
+            let confirmation = prompt::confirm(yes, &format!("Delete function app {name}?"))?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Deleting function app...").green().bold().to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            match delete_fun_app(client, &project_id, &name).await {
+                Ok(_) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{name} has been deleted."),
+                    )
+                    .with_data(json!({ "name": name })))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Invoke { name, data } => {
+            let payload = read_payload(data)?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style(format!("Invoking {name}..."))
+                    .green()
+                    .bold()
+                    .to_string(),
+            );
+            let started = Instant::now();
+            match invoke_fun_app(client, &project_id, &name, payload).await {
+                Ok(body) => {
+                    let duration_ms = started.elapsed().as_millis();
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    // Functions usually answer JSON, but do not have to.
+                    let response = serde_json::from_str(&body).unwrap_or(Value::String(body));
+                    if output_format() == OutputFormat::Table {
+                        match &response {
+                            Value::String(body) => println!("{body}"),
+                            response => println!("{}", serde_json::to_string_pretty(response)?),
+                        }
+                    }
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{name} responded in {duration_ms} ms."),
+                    )
+                    .with_data(json!({
+                        "name": name,
+                        "duration_ms": duration_ms,
+                        "response": response,
+                    })))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+    }
+}
+
+impl Tabular for FunApp {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("ID", 5),
+            ("Name", 20),
+            ("Runtime", 12),
+            ("Memory", 8),
+            ("Created at", 20),
+            ("Updated at", 20),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.runtime.clone(),
+            format!("{} MB", self.memory_mb),
+            self.created_at.date_naive().to_string(),
+            self.updated_at.date_naive().to_string(),
+        ]
+    }
+}
+
+// Private functions
+
+/// Memory typed in when asked, held to the same range as `--memory`.
+fn parse_memory(memory: &str) -> Result<u32, SmbError> {
+    memory
+        .trim()
+        .parse()
+        .ok()
+        .filter(|memory| (MIN_MEMORY_MB..=MAX_MEMORY_MB).contains(memory))
+        .ok_or_else(|| SmbError::Validation {
+            message: format!(
+                "Memory has to be a number of MB from {MIN_MEMORY_MB} to {MAX_MEMORY_MB}."
+            ),
+            errors: BTreeMap::new(),
+        })
+}
+
+/// `--data` like curl: `@file` reads a file, `@-` reads stdin, anything else is the payload.
+fn read_payload(data: Option<String>) -> Result<Vec<u8>> {
+    let payload = match data.as_deref() {
+        None => b"{}".to_vec(),
+        Some("@-") => {
+            let mut payload = Vec::new();
+            std::io::stdin().lock().read_to_end(&mut payload)?;
+            payload
+        }
+        Some(data) => match data.strip_prefix('@') {
+            Some(path) => std::fs::read(path).with_context(|| format!("Failed to read {path}."))?,
+            None => data.as_bytes().to_vec(),
+        },
+    };
+    if let Err(e) = serde_json::from_slice::<Value>(&payload) {
+        return Err(SmbError::Validation {
+            message: "The payload is not valid JSON.".to_owned(),
+            errors: BTreeMap::from([("data".to_owned(), vec![e.to_string()])]),
+        }
+        .into());
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Status;
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("512").unwrap(), 512);
+        assert_eq!(parse_memory(" 4096 ").unwrap(), 4096);
+        for memory in ["64", "8192", "lots", ""] {
+            let error = anyhow::Error::new(parse_memory(memory).unwrap_err());
+            assert_eq!(Status::from_error(&error), Status::Validation);
+        }
+    }
+}
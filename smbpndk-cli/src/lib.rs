@@ -1,3 +1,9 @@
 pub mod account;
+pub mod app_oten;
 pub mod cli;
+pub mod deploy;
+pub mod fun_app;
+pub mod pkt_app;
+pub mod profile;
 pub mod project;
+pub mod rdb_app;
@@ -1,17 +1,29 @@
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use console::style;
-use smbpndk_cli::cli::CommandResult;
+use smbpndk_cli::cli::{error_message, output, prompt, CommandResult, Status};
 use smbpndk_cli::{
     account::process_account,
+    app_oten::process_oten,
     cli::{Cli, Commands},
+    deploy::{
+        deployments::{process_deployments, process_rollback},
+        process_deploy,
+    },
+    fun_app::{logs::process_logs, process_fun},
+    pkt_app::process_pkg,
+    profile::process_profile,
     project::process_project,
+    rdb_app::process_db,
 };
-use std::{
-    fs::{create_dir_all, OpenOptions},
-    path::PathBuf,
-    str::FromStr,
+use smbpndk_networking::{
+    client::SmbClient, environment::Environment, get_smb_token, smb_token_file_path,
 };
+use smbpndk_utils::{
+    get_config,
+    profile::{active_profile, create_private_dir, profile_exists, set_profile_override},
+};
+use std::{fs::OpenOptions, path::PathBuf, str::FromStr, time::Duration};
 use tracing::subscriber::set_global_default;
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_subscriber::{filter::LevelFilter, prelude::*, EnvFilter};
@@ -20,12 +32,12 @@ fn setup_logging(level: Option<EnvFilter>) -> Result<()> {
     // Log in the current directory
     let log_path = match home::home_dir() {
         Some(path) => {
-            create_dir_all(path.join(".smb"))?;
+            create_private_dir(&path.join(".smb"))?;
             let log_path = [path.to_str().unwrap(), "/.smb/smbpndk-cli.log"].join("");
             // Create the file if it doesn't exist
             let _file = OpenOptions::new()
                 .create(true)
-                .write(true)
+                .append(true)
                 .open(&log_path)?;
 
             PathBuf::from(log_path)
@@ -37,7 +49,7 @@ fn setup_logging(level: Option<EnvFilter>) -> Result<()> {
 
     let file = OpenOptions::new()
         .create(true)
-        .write(true)
+        .append(true)
         .open(log_path)
         .unwrap();
 
@@ -63,17 +75,24 @@ fn setup_logging(level: Option<EnvFilter>) -> Result<()> {
 async fn main() {
     match run().await {
         Ok(result) => {
+            let symbol = result.symbol();
+            let exit_code = result.status.exit_code();
             let mut spinner = result.spinner;
-            spinner.stop_and_persist(&result.symbol, result.msg);
-            std::process::exit(1);
+            spinner.stop_and_persist(symbol, result.msg);
+            if let Some(data) = result.data {
+                if let Err(e) = output::print_value(&data) {
+                    eprintln!("{} {}", style("✘").for_stderr().red(), e);
+                }
+            }
+            std::process::exit(exit_code);
         }
         Err(e) => {
-            println!(
+            eprintln!(
                 "\n{} {}",
                 style("✘".to_string()).for_stderr().red(),
-                style(e).red()
+                style(error_message(&e)).red()
             );
-            std::process::exit(1);
+            std::process::exit(Status::from_error(&e).exit_code());
         }
     }
 }
@@ -96,9 +115,92 @@ async fn run() -> Result<CommandResult> {
         setup_logging(None)?;
     }
 
+    prompt::set_no_input(cli.no_input);
+    output::set_output_format(cli.output);
+
+    if let Some(profile) = &cli.profile {
+        set_profile_override(profile)?;
+    }
+    let profile = active_profile();
+    // Profile commands work on the profiles themselves, e.g. to add the one asked for.
+    if !matches!(cli.command, Commands::Profile { .. }) && !profile_exists(&profile)? {
+        return Err(anyhow!(
+            "Profile {profile} does not exist. Create it with smb profile add {profile}."
+        ));
+    }
+
+    let config_environment = get_config().await.ok().and_then(|c| c.environment);
+    let environment = Environment::resolve(
+        cli.api_url.as_deref(),
+        cli.env.as_deref(),
+        config_environment.as_deref(),
+    )?;
+    let token = match get_smb_token().await {
+        Ok(token) => Some(token),
+        Err(e) => {
+            // Only worth mentioning when there is a token we cannot use.
+            if smb_token_file_path().is_some() {
+                eprintln!("{} {}", style("!").for_stderr().yellow(), e);
+            }
+            None
+        }
+    };
+    let client = SmbClient::new(environment, token)?;
+
     match cli.command {
-        Commands::Account { command } => process_account(command).await,
-        Commands::Project { command } => process_project(command).await,
-        //Commands::Oten { command } => process_auth_app(command).await,
+        Commands::Account { command } => process_account(&client, command).await,
+        Commands::Project { command } => process_project(&client, command).await,
+        Commands::Profile { command } => process_profile(command, cli.api_url.or(cli.env)).await,
+        Commands::Oten {
+            project_id,
+            command,
+        } => process_oten(&client, project_id, command).await,
+        Commands::Fun {
+            project_id,
+            command,
+        } => process_fun(&client, project_id, command).await,
+        Commands::Deploy {
+            app,
+            path,
+            project_id,
+            no_wait,
+            timeout,
+        } => {
+            process_deploy(
+                &client,
+                project_id,
+                app,
+                &path,
+                no_wait,
+                Duration::from_secs(timeout),
+            )
+            .await
+        }
+        Commands::Logs {
+            app,
+            follow,
+            since,
+            filter,
+            project_id,
+        } => process_logs(&client, project_id, app, follow, since, filter).await,
+        Commands::Deployments {
+            project_id,
+            app,
+            command,
+        } => process_deployments(&client, project_id, app, command).await,
+        Commands::Rollback {
+            id,
+            app,
+            project_id,
+            yes,
+        } => process_rollback(&client, project_id, app, id, yes).await,
+        Commands::Db {
+            project_id,
+            command,
+        } => process_db(&client, project_id, command).await,
+        Commands::Pkg {
+            project_id,
+            command,
+        } => process_pkg(&client, project_id, command).await,
     }
 }
@@ -0,0 +1,48 @@
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[clap(
+        about = "Package a directory with a smbpkg.yaml manifest and publish it as a new version. Files matching .smbignore are left out."
+    )]
+    Publish {
+        /// Directory with the manifest
+        #[clap(long, default_value = ".")]
+        path: PathBuf,
+        /// Sign the tarball with this key, made with smb pkg keygen
+        #[clap(long)]
+        sign_key: Option<PathBuf>,
+        /// Package and show what would be published, without publishing
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    #[clap(
+        about = "Yank a version so it is no longer picked for new installs. Installs already using it keep working."
+    )]
+    Yank {
+        /// Version to yank, e.g. image-tools@1.2.0
+        #[clap(value_parser = parse_name_version)]
+        package: (String, String),
+        /// Yank without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Create a key to sign packages with. Keep it private.")]
+    Keygen {
+        /// File to write the key to. It must not exist yet.
+        path: PathBuf,
+    },
+}
+
+/// Splits `name@version`.
+fn parse_name_version(package: &str) -> Result<(String, String), String> {
+    match package.split_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => {
+            Ok((name.to_owned(), version.to_owned()))
+        }
+        _ => Err(format!("{package} is not like name@version")),
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// Describes the package in the directory it is in.
+pub const MANIFEST_FILE: &str = "smbpkg.yaml";
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Lowercase letters, digits, `-` and `_`, starting with a letter.
+    pub name: String,
+    /// A semantic version like `1.2.0` or `2.0.0-beta.1`.
+    pub version: String,
+    pub description: Option<String>,
+}
+
+/// Reads and checks the manifest of `dir`.
+pub fn read_manifest(dir: &Path) -> Result<Manifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let contents = fs::read_to_string(&path).with_context(|| {
+        format!(
+            "Failed to read {}. A package needs a {MANIFEST_FILE} with its name and version.",
+            path.display()
+        )
+    })?;
+    parse_manifest(&contents).with_context(|| format!("Invalid {}.", path.display()))
+}
+
+// Private functions
+
+fn parse_manifest(contents: &str) -> Result<Manifest> {
+    let manifest: Manifest = serde_yaml::from_str(contents)?;
+
+    let name_pattern = Regex::new("^[a-z][a-z0-9_-]{0,63}$")?;
+    if !name_pattern.is_match(&manifest.name) {
+        return Err(anyhow!(
+            "The name {} may only have lowercase letters, digits, - and _, and must start with a letter.",
+            manifest.name
+        ));
+    }
+    semver::Version::parse(&manifest.version).map_err(|e| {
+        anyhow!(
+            "The version {} is not a semantic version like 1.2.0: {e}.",
+            manifest.version
+        )
+    })?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest =
+            parse_manifest("name: image-tools\nversion: 1.2.0-beta.1\ndescription: Resizing\n")
+                .unwrap();
+        assert_eq!(
+            manifest,
+            Manifest {
+                name: "image-tools".to_owned(),
+                version: "1.2.0-beta.1".to_owned(),
+                description: Some("Resizing".to_owned()),
+            }
+        );
+
+        assert!(parse_manifest("name: Image Tools\nversion: 1.2.0\n").is_err());
+        assert!(parse_manifest("name: image-tools\nversion: 1.2\n").is_err());
+        assert!(parse_manifest("name: image-tools\n").is_err());
+        assert!(parse_manifest("name: image-tools\nversion: 1.2.0\nlicense: MIT\n").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+pub mod cli;
+mod manifest;
+mod signing;
+
+use self::{
+    cli::Commands,
+    manifest::read_manifest,
+    signing::{generate_key, is_key, sign},
+};
+use crate::{
+    cli::{
+        output::{output_format, print_one, OutputFormat, Tabular},
+        prompt, CommandResult,
+    },
+    deploy::archive::package_excluding,
+    project::project_id_or_current,
+};
+use anyhow::Result;
+use console::style;
+use indicatif::HumanBytes;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use smbpndk_model::app_pkt::{PackageVersion, PackageVersionCreate};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_pkt::{
+    create_package_version, upload_package_tarball, yank_package_version,
+};
+use spinners::Spinner;
+
+/// `project_id` is the `--project-id` given on the command line, if any.
+pub async fn process_pkg(
+    client: &SmbClient,
+    project_id: Option<String>,
+    commands: Commands,
+) -> Result<CommandResult> {
+    match commands {
+        Commands::Publish {
+            path,
+            sign_key,
+            dry_run,
+        } => {
+            let manifest = read_manifest(&path)?;
+            let package_name = format!("{}@{}", manifest.name, manifest.version);
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style(format!("Packaging {package_name}..."))
+                    .green()
+                    .bold()
+                    .to_string(),
+            );
+            // Keys must never be published, even when they are in the package directory.
+            let archive = match package_excluding(&path, is_key) {
+                Ok(archive) => {
+                    spinner.stop_and_persist(
+                        "✅",
+                        format!(
+                            "Packaged {} files ({}).",
+                            archive.files,
+                            HumanBytes(archive.bytes.len() as u64)
+                        ),
+                    );
+                    archive
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    return Err(e);
+                }
+            };
+            let checksum = hex::encode(Sha256::digest(&archive.bytes));
+            let signature = match &sign_key {
+                Some(sign_key) => Some(sign(sign_key, &archive.bytes)?),
+                None => None,
+            };
+
+            if dry_run {
+                return Ok(CommandResult::success(
+                    Spinner::new(
+                        spinners::Spinners::SimpleDotsScrolling,
+                        style("Loading...").green().bold().to_string(),
+                    ),
+                    format!("Dry run, {package_name} was not published. SHA-256 {checksum}."),
+                )
+                .with_data(json!({
+                    "name": manifest.name,
+                    "version": manifest.version,
+                    "checksum": checksum,
+                    "size_bytes": archive.bytes.len(),
+                    "files": archive.files,
+                    "signed": signature.is_some(),
+                })));
+            }
+
+            let project_id = project_id_or_current(project_id).await?;
+            let package_version = PackageVersionCreate {
+                version: manifest.version.clone(),
+                description: manifest.description,
+                checksum,
+                size_bytes: archive.bytes.len() as u64,
+                signature,
+            };
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style(format!("Publishing {package_name}..."))
+                    .green()
+                    .bold()
+                    .to_string(),
+            );
+            let published =
+                match create_package_version(client, &project_id, &manifest.name, package_version)
+                    .await
+                {
+                    Ok(_) => {
+                        upload_package_tarball(
+                            client,
+                            &project_id,
+                            &manifest.name,
+                            &manifest.version,
+                            archive.bytes.len() as u64,
+                            archive.bytes,
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                };
+            match published {
+                Ok(package_version) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&package_version)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{package_name} has been published."),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Yank {
+            package: (name, version),
+            yes,
+        } => {
+            let project_id = project_id_or_current(project_id).await?;
+            let confirmation = prompt::confirm(
+                yes,
+                &format!("Yank {name}@{version}? New installs will no longer pick it."),
+            )?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style(format!("Yanking {name}@{version}..."))
+                    .green()
+                    .bold()
+                    .to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            match yank_package_version(client, &project_id, &name, &version).await {
+                Ok(package_version) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&package_version)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{name}@{version} has been yanked."),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        Commands::Keygen { path } => {
+            let public_key = generate_key(&path)?;
+            if output_format() == OutputFormat::Table {
+                println!("Public key: {public_key}");
+            }
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                format!(
+                    "Key written to {}. Sign with smb pkg publish --sign-key {}.",
+                    path.display(),
+                    path.display()
+                ),
+            )
+            .with_data(json!({ "path": path, "public_key": public_key })))
+        }
+    }
+}
+
+impl Tabular for PackageVersion {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("Name", 20),
+            ("Version", 14),
+            ("SHA-256", 12),
+            ("Size", 10),
+            ("Signed", 6),
+            ("Yanked", 6),
+            ("Created at", 17),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_owned();
+        vec![
+            self.name.clone(),
+            self.version.clone(),
+            self.checksum.chars().take(12).collect(),
+            HumanBytes(self.size_bytes).to_string(),
+            yes_no(self.signed),
+            yes_no(self.yanked),
+            self.created_at.format("%Y-%m-%d %H:%M").to_string(),
+        ]
+    }
+}
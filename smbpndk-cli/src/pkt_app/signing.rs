@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Context, Result};
+use ed25519_dalek::{Signer, SigningKey, SECRET_KEY_LENGTH};
+use smbpndk_model::app_pkt::PackageSignature;
+use smbpndk_utils::profile::create_private_dir;
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+const ALGORITHM: &str = "ed25519";
+/// A hex encoded seed on a line, with room for surrounding whitespace.
+const MAX_KEY_FILE_BYTES: u64 = 128;
+
+/// Writes a new Ed25519 key to `path`, readable only by the user, and returns its public key
+/// hex encoded. Refuses to overwrite an existing file, as that would lose the key in it.
+/// A missing parent directory is created, accessible only by the user.
+pub fn generate_key(path: &Path) -> Result<String> {
+    let mut seed = [0u8; SECRET_KEY_LENGTH];
+    getrandom::getrandom(&mut seed).map_err(|e| anyhow!("Failed to generate a key: {e}"))?;
+    let key = SigningKey::from_bytes(&seed);
+
+    if let Some(dir) = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+    {
+        create_private_dir(dir).with_context(|| format!("Failed to create {}.", dir.display()))?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create {}.", path.display()))?;
+    writeln!(file, "{}", hex::encode(seed))?;
+    file.sync_all()?;
+
+    Ok(hex::encode(key.verifying_key().to_bytes()))
+}
+
+/// Signs `bytes` with the key `generate_key` wrote to `path`.
+pub fn sign(path: &Path, bytes: &[u8]) -> Result<PackageSignature> {
+    let key = read_key(path)?;
+    Ok(PackageSignature {
+        algorithm: ALGORITHM.to_owned(),
+        public_key: hex::encode(key.verifying_key().to_bytes()),
+        signature: hex::encode(key.sign(bytes).to_bytes()),
+    })
+}
+
+/// Whether `path` holds a key like the ones `generate_key` writes, so that it is left out of
+/// packages wherever it is.
+pub fn is_key(path: &Path) -> bool {
+    // Larger files cannot be keys, no need to read them.
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.len() <= MAX_KEY_FILE_BYTES)
+        && std::fs::read_to_string(path).is_ok_and(|contents| parse_key(&contents).is_some())
+}
+
+// Private functions
+
+fn read_key(path: &Path) -> Result<SigningKey> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read the key {}.", path.display()))?;
+    parse_key(&contents)
+        .ok_or_else(|| anyhow!("{} is not a key made with smb pkg keygen.", path.display()))
+}
+
+fn parse_key(contents: &str) -> Option<SigningKey> {
+    let seed: [u8; SECRET_KEY_LENGTH] = hex::decode(contents.trim()).ok()?.try_into().ok()?;
+    Some(SigningKey::from_bytes(&seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    #[test]
+    fn test_sign() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys/smbpkg.key");
+        let public_key = generate_key(&path).unwrap();
+        assert!(generate_key(&path).is_err(), "keys are never overwritten");
+        assert!(is_key(&path));
+
+        let signature = sign(&path, b"tarball").unwrap();
+        assert_eq!(signature.algorithm, "ed25519");
+        assert_eq!(signature.public_key, public_key);
+
+        let public_key: [u8; 32] = hex::decode(&public_key).unwrap().try_into().unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public_key).unwrap();
+        let signature: [u8; 64] = hex::decode(&signature.signature)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let signature = Signature::from_bytes(&signature);
+        assert!(verifying_key.verify(b"tarball", &signature).is_ok());
+        assert!(verifying_key.verify(b"tampered", &signature).is_err());
+
+        std::fs::write(&path, "not a key").unwrap();
+        assert!(sign(&path, b"tarball").is_err());
+        assert!(!is_key(&path));
+    }
+}
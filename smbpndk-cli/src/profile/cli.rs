@@ -0,0 +1,28 @@
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[clap(about = "List all profiles.")]
+    List {},
+
+    #[clap(about = "Add a profile. Pass --env or --api-url to set its API environment.")]
+    Add {
+        /// Profile name
+        #[clap(required = true)]
+        name: String,
+    },
+
+    #[clap(about = "Remove a profile with its token and config.")]
+    Remove {
+        /// Profile name
+        #[clap(required = true)]
+        name: String,
+    },
+
+    #[clap(about = "Switch the active profile.")]
+    Switch {
+        /// Profile name
+        #[clap(required = true)]
+        name: String,
+    },
+}
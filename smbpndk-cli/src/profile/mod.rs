@@ -0,0 +1,136 @@
+pub mod cli;
+
+use self::cli::Commands;
+use crate::cli::{
+    output::{print_list, Tabular},
+    CommandResult,
+};
+use anyhow::Result;
+use console::style;
+use serde::Serialize;
+use serde_json::json;
+use smbpndk_model::project::Config;
+use smbpndk_networking::{constants::TOKEN_FILE_NAME, environment::Environment};
+use smbpndk_utils::{
+    get_profile_config,
+    profile::{
+        active_profile, add_profile, list_profiles, profile_dir, remove_profile, switch_profile,
+    },
+    write_profile_config,
+};
+use spinners::Spinner;
+
+/// `environment` is the `--env` or `--api-url` given on the command line, if any.
+pub async fn process_profile(
+    commands: Commands,
+    environment: Option<String>,
+) -> Result<CommandResult> {
+    match commands {
+        Commands::List {} => {
+            show_profiles().await?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                format!("Active profile: {}.", active_profile()),
+            ))
+        }
+        Commands::Add { name } => {
+            // Validate before creating anything.
+            if let Some(environment) = &environment {
+                environment.parse::<Environment>()?;
+            }
+            add_profile(&name)?;
+            write_profile_config(
+                &name,
+                Config {
+                    current_project: None,
+                    current_auth_app: None,
+                    environment,
+                },
+            )?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                format!("Profile {name} has been added. Use it with smb --profile {name}."),
+            )
+            .with_data(json!({ "name": name })))
+        }
+        Commands::Remove { name } => {
+            remove_profile(&name)?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                format!("Profile {name} has been removed."),
+            )
+            .with_data(json!({ "name": name })))
+        }
+        Commands::Switch { name } => {
+            switch_profile(&name)?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                format!("Switched to profile {name}."),
+            )
+            .with_data(json!({ "name": name })))
+        }
+    }
+}
+
+// Private functions
+
+#[derive(Serialize)]
+struct ProfileRow {
+    name: String,
+    active: bool,
+    environment: Option<String>,
+    logged_in: bool,
+}
+
+impl Tabular for ProfileRow {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("Active", 6),
+            ("Name", 20),
+            ("Environment", 40),
+            ("Logged in", 10),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            if self.active { "*" } else { "" }.to_owned(),
+            self.name.clone(),
+            self.environment
+                .clone()
+                .unwrap_or_else(|| "default".to_owned()),
+            if self.logged_in { "yes" } else { "no" }.to_owned(),
+        ]
+    }
+}
+
+async fn show_profiles() -> Result<()> {
+    let active = active_profile();
+    let mut rows = vec![];
+    for name in list_profiles()? {
+        let environment = get_profile_config(&name)
+            .await
+            .ok()
+            .and_then(|config| config.environment);
+        let logged_in = profile_dir(&name)?.join(TOKEN_FILE_NAME).is_file();
+        rows.push(ProfileRow {
+            active: name == active,
+            name,
+            environment,
+            logged_in,
+        });
+    }
+    print_list(&rows)
+}
@@ -3,28 +3,66 @@ use clap::Subcommand;
 #[derive(Subcommand)]
 pub enum Commands {
     #[clap(about = "Add new project.")]
-    New {},
+    New {
+        /// Project name
+        #[clap(short, long)]
+        name: Option<String>,
+        /// Project description
+        #[clap(short, long)]
+        description: Option<String>,
+    },
 
     #[clap(about = "List all your projects.")]
     List {},
 
     #[clap(about = "Show detail of a project.")]
     Show {
+        /// Project name or id. Defaults to the current project.
+        project: Option<String>,
+        /// Project Id
+        #[clap(short, long, conflicts_with = "project")]
+        id: Option<String>,
+    },
+
+    #[clap(about = "Edit the name or description of a project.")]
+    Edit {
+        /// Project name or id. Defaults to the current project.
+        project: Option<String>,
         /// Project Id
-        #[clap(short, long, required = true)]
-        id: String,
+        #[clap(short, long, conflicts_with = "project")]
+        id: Option<String>,
+        /// New project name
+        #[clap(short, long)]
+        name: Option<String>,
+        /// New project description
+        #[clap(short, long)]
+        description: Option<String>,
     },
 
     #[clap(about = "Delete a project.")]
     Delete {
-        /// Project name
-        #[clap(short, long, required = true)]
-        id: String,
+        /// Project name or id. Pick from a list when omitted.
+        project: Option<String>,
+        /// Project Id
+        #[clap(short, long, conflicts_with = "project")]
+        id: Option<String>,
+        /// Delete without confirmation
+        #[clap(short, long)]
+        yes: bool,
     },
 
     #[clap(about = "Use project for current CLI session.")]
     Use {
-        #[clap(short, long, required = true)]
-        id: String,
+        /// Project name or id. Pick from a list when omitted.
+        project: Option<String>,
+        /// Project Id
+        #[clap(short, long, conflicts_with = "project")]
+        id: Option<String>,
     },
+
+    #[clap(about = "Show the project used for the current CLI session.")]
+    Current {},
+
+    #[clap(about = "Stop using the current project.")]
+    Unuse {},
 }
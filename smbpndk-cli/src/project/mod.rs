@@ -1,60 +1,60 @@
 pub mod cli;
 
 use self::cli::Commands;
-use crate::cli::CommandResult;
-use anyhow::{anyhow, Result};
+use crate::cli::{
+    output::{print_list, print_one, Tabular},
+    prompt::{self, MissingInput},
+    CommandResult,
+};
+use anyhow::Result;
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Input};
-use log::debug;
+use serde_json::json;
 use smbpndk_model::{
     self,
-    project::{Config, Project, ProjectCreate},
+    project::{Config, Project, ProjectCreate, ProjectUpdate},
+};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use smbpndk_networking_project::{
+    create_project, delete_project, get_all, get_project, update_project,
 };
-use smbpndk_networking_project::{create_project, delete_project, get_all, get_project};
+use smbpndk_utils::{get_config, write_config};
 use spinners::Spinner;
-use std::{fs::OpenOptions, io::Write};
+use std::collections::BTreeMap;
 
-pub async fn process_project(commands: Commands) -> Result<CommandResult> {
+pub async fn process_project(client: &SmbClient, commands: Commands) -> Result<CommandResult> {
     match commands {
-        Commands::New {} => {
-            let project_name = Input::<String>::with_theme(&ColorfulTheme::default())
-                .with_prompt("Project name")
-                .interact()
-                .unwrap();
-            let description = Input::<String>::with_theme(&ColorfulTheme::default())
-                .with_prompt("Description")
-                .interact()
-                .unwrap();
+        Commands::New { name, description } => {
+            let project_name = prompt::text(name, "Project name", "--name")?;
+            let description = prompt::text(description, "Description", "--description")?;
 
             let mut spinner = Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Creating a project...").green().bold().to_string(),
             );
 
-            match create_project(ProjectCreate {
-                name: project_name.clone(),
-                description: description.clone(),
-            })
+            match create_project(
+                client,
+                ProjectCreate {
+                    name: project_name.clone(),
+                    description: description.clone(),
+                },
+            )
             .await
             {
-                Ok(_) => {
+                Ok(project) => {
                     spinner.stop_and_persist("✅", "Done.".to_owned());
-                    Ok(CommandResult {
-                        spinner: Spinner::new(
+                    print_one(&project)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
                             spinners::Spinners::SimpleDotsScrolling,
                             style("Loading...").green().bold().to_string(),
                         ),
-                        symbol: "✅".to_owned(),
-                        msg: format!("{project_name} has been created."),
-                    })
+                        format!("{project_name} has been created."),
+                    ))
                 }
                 Err(e) => {
-                    println!("Error: {e:#?}");
-                    Ok(CommandResult {
-                        spinner,
-                        symbol: "😩".to_owned(),
-                        msg: format!("Failed to create a project {project_name}."),
-                    })
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
                 }
             }
         }
@@ -65,7 +65,7 @@ pub async fn process_project(commands: Commands) -> Result<CommandResult> {
             );
 
             // Get all
-            match get_all().await {
+            match get_all(client).await {
                 Ok(projects) => {
                     spinner.stop_and_persist("✅", "Loaded.".to_owned());
                     let msg = if projects.is_empty() {
@@ -73,144 +73,292 @@ pub async fn process_project(commands: Commands) -> Result<CommandResult> {
                     } else {
                         "Showing all projects.".to_owned()
                     };
-                    show_projects(projects);
-                    Ok(CommandResult {
-                        spinner: Spinner::new(
+                    print_list(&projects)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
                             spinners::Spinners::SimpleDotsScrolling,
                             style("Loading...").green().bold().to_string(),
                         ),
-                        symbol: "✅".to_owned(),
                         msg,
-                    })
+                    ))
                 }
                 Err(e) => {
-                    println!("Error: {e:#?}");
-                    Ok(CommandResult {
-                        spinner,
-                        symbol: "😩".to_owned(),
-                        msg: "Failed to get all projects.".to_owned(),
-                    })
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
                 }
             }
         }
-        Commands::Show { id } => {
+        Commands::Show { project, id } => {
+            let id = or_current_project(&project, id).await?;
+            let project = resolve_project(client, project, id).await?;
+            let message = format!("Showing project {}.", &project.name);
+            print_one(&project)?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                message,
+            ))
+        }
+        Commands::Edit {
+            project,
+            id,
+            name,
+            description,
+        } => {
+            let id = or_current_project(&project, id).await?;
+            let project = resolve_project(client, project, id).await?;
+            let id = project.id.to_string();
+            let update = if name.is_some() || description.is_some() {
+                ProjectUpdate { name, description }
+            } else if prompt::is_interactive() {
+                let name = prompt::edit("Project name", &project.name)?;
+                let description = prompt::edit("Description", &project.description)?;
+                ProjectUpdate {
+                    name: (name != project.name).then_some(name),
+                    description: (description != project.description).then_some(description),
+                }
+            } else {
+                return Err(MissingInput(
+                    "Nothing to change. Pass --name or --description.".to_owned(),
+                )
+                .into());
+            };
+
+            if update.name.is_none() && update.description.is_none() {
+                return Ok(CommandResult::success(
+                    Spinner::new(
+                        spinners::Spinners::SimpleDotsScrolling,
+                        style("Loading...").green().bold().to_string(),
+                    ),
+                    "Nothing to change.",
+                ));
+            }
+
             let mut spinner = Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
-                style("Loading...").green().bold().to_string(),
+                style("Updating project...").green().bold().to_string(),
             );
-            // Get Detail
-            match get_project(id).await {
+            match update_project(client, id, update).await {
                 Ok(project) => {
-                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
-                    let message = format!("Showing project {}.", &project.name);
-                    show_projects(vec![project]);
-                    Ok(CommandResult {
-                        spinner: Spinner::new(
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&project)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
                             spinners::Spinners::SimpleDotsScrolling,
                             style("Loading...").green().bold().to_string(),
                         ),
-                        symbol: "✅".to_owned(),
-                        msg: message,
-                    })
+                        format!("{} has been updated.", project.name),
+                    ))
                 }
                 Err(e) => {
-                    spinner.stop_and_persist("😩", "Failed.".to_string());
-                    Err(anyhow!("{e}"))
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
                 }
             }
         }
-        Commands::Delete { id } => {
-            let confirmation = Input::<String>::with_theme(&ColorfulTheme::default())
-                .with_prompt("Are you sure? (y/n)")
-                .interact()
-                .unwrap();
+        Commands::Delete { project, id, yes } => {
+            let project = resolve_project(client, project, id).await?;
+            let id = project.id.to_string();
+            let confirmation = prompt::confirm(yes, &format!("Delete project {project}?"))?;
 
             let mut spinner = Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Deleting project...").green().bold().to_string(),
             );
 
-            if confirmation != "y" {
-                return Ok(CommandResult {
-                    spinner,
-                    symbol: "✅".to_owned(),
-                    msg: "Cancelled.".to_string(),
-                });
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
             }
-            match delete_project(id).await {
+            match delete_project(client, id.clone()).await {
                 Ok(_) => {
                     spinner.stop_and_persist("✅", "Done.".to_string());
-                    Ok(CommandResult {
-                        spinner: Spinner::new(
+                    Ok(CommandResult::success(
+                        Spinner::new(
                             spinners::Spinners::SimpleDotsScrolling,
                             style("Loading...").green().bold().to_string(),
                         ),
-                        symbol: "✅".to_owned(),
-                        msg: "Project has been deleted.".to_string(),
-                    })
+                        "Project has been deleted.",
+                    )
+                    .with_data(json!({ "id": id })))
                 }
                 Err(e) => {
                     spinner.stop_and_persist("😩", "Failed.".to_string());
-                    Err(anyhow!("{e}"))
+                    Err(e.into())
                 }
             }
         }
-        Commands::Use { id } => {
-            let project = get_project(id).await?;
+        Commands::Use { project, id } => {
+            let project = resolve_project(client, project, id).await?;
 
+            let data = serde_json::to_value(&project)?;
             let config = Config {
                 current_project: Some(project),
-                current_auth_app: None,
+                ..get_config().await?
             };
 
             let spinner = Spinner::new(
                 spinners::Spinners::SimpleDotsScrolling,
                 style("Loading...").green().bold().to_string(),
             );
-            match home::home_dir() {
-                Some(path) => {
-                    debug!("{}", path.to_str().unwrap());
-                    let mut file = OpenOptions::new()
-                        .create(true)
-                        .write(true)
-                        .open([path.to_str().unwrap(), "/.smb/config.json"].join(""))?;
-                    let json = serde_json::to_string(&config)?;
-                    file.write_all(json.as_bytes())?;
-
-                    Ok(CommandResult {
-                        spinner,
-                        symbol: "✅".to_owned(),
-                        msg: "Use project successful.".to_string(),
-                    })
-                }
-                None => {
-                    let error = anyhow!("Failed to get home directory.");
-                    Err(error)
-                }
+            write_config(config)?;
+
+            Ok(CommandResult::success(spinner, "Use project successful.").with_data(data))
+        }
+        Commands::Current {} => match get_config().await?.current_project {
+            Some(project) => {
+                let message = format!("Using project {}.", &project.name);
+                print_one(&project)?;
+                Ok(CommandResult::success(
+                    Spinner::new(
+                        spinners::Spinners::SimpleDotsScrolling,
+                        style("Loading...").green().bold().to_string(),
+                    ),
+                    message,
+                ))
             }
+            None => Err(SmbError::NotFound(
+                "No project in use. Pick one with smb project use.".to_owned(),
+            )
+            .into()),
+        },
+        Commands::Unuse {} => {
+            let config = Config {
+                current_project: None,
+                ..get_config().await?
+            };
+            write_config(config)?;
+            Ok(CommandResult::success(
+                Spinner::new(
+                    spinners::Spinners::SimpleDotsScrolling,
+                    style("Loading...").green().bold().to_string(),
+                ),
+                "Not using any project.",
+            ))
         }
     }
 }
 
+/// The project a project-scoped command works on: `project_id` when given, otherwise the current project.
+pub async fn project_id_or_current(project_id: Option<String>) -> Result<String> {
+    if let Some(project_id) = project_id {
+        return Ok(project_id);
+    }
+    match get_config().await?.current_project {
+        Some(project) => Ok(project.id.to_string()),
+        None => Err(MissingInput(
+            "No project selected. Pass --project-id or pick one with smb project use.".to_owned(),
+        )
+        .into()),
+    }
+}
+
 // Private functions
 
-fn show_projects(projects: Vec<Project>) {
-    // println!("Projects: {projects:#?}");
-    if projects.is_empty() {
-        return;
+/// Finds a project by `--id`, by the name or id given positionally, or lets the user pick one.
+async fn resolve_project(
+    client: &SmbClient,
+    project: Option<String>,
+    id: Option<String>,
+) -> Result<Project> {
+    if project.is_none() && id.is_none() && !prompt::is_interactive() {
+        return Err(MissingInput(
+            "Missing project name or --id. It is required when running without input.".to_owned(),
+        )
+        .into());
     }
-    println!(
-        "{0: <5} | {1: <20} | {2: <30} | {3: <20} | {4: <20}",
-        "ID", "Name", "Description", "Created at", "Updated at"
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Loading...").green().bold().to_string(),
     );
-    for project in projects {
-        println!(
-            "{0: <5} | {1: <20} | {2: <30} | {3: <20} | {4: <20}",
-            project.id,
-            project.name,
-            project.description,
-            project.created_at.date_naive(),
-            project.updated_at.date_naive(),
-        );
+    let result = match &id {
+        Some(id) => get_project(client, id.clone())
+            .await
+            .map(|project| vec![project]),
+        None => get_all(client).await,
+    };
+    let mut projects = match result {
+        Ok(projects) => {
+            spinner.stop_and_persist("✅", "Loaded.".to_owned());
+            projects
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            return Err(e.into());
+        }
+    };
+
+    if id.is_some() {
+        return Ok(projects.remove(0));
+    }
+    match project {
+        Some(name_or_id) => find_project(projects, &name_or_id),
+        None if projects.is_empty() => Err(SmbError::NotFound(
+            "No projects found. Create one with smb project new.".to_owned(),
+        )
+        .into()),
+        None => prompt::fuzzy_select(&projects, "Project", "a project name or --id"),
+    }
+}
+
+/// Without a project or `--id`, falls back to the project set with `smb project use`.
+async fn or_current_project(
+    project: &Option<String>,
+    id: Option<String>,
+) -> Result<Option<String>> {
+    if project.is_some() || id.is_some() {
+        return Ok(id);
+    }
+    let current = get_config().await?.current_project;
+    Ok(current.map(|project| project.id.to_string()))
+}
+
+/// An id takes precedence over a project with that name.
+fn find_project(projects: Vec<Project>, name_or_id: &str) -> Result<Project> {
+    if let Some(project) = projects.iter().find(|p| p.id.to_string() == name_or_id) {
+        return Ok(project.clone());
+    }
+    let mut matches: Vec<Project> = projects
+        .into_iter()
+        .filter(|project| project.name == name_or_id)
+        .collect();
+    match matches.len() {
+        0 => Err(SmbError::NotFound(format!("Project {name_or_id} not found.")).into()),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let ids: Vec<String> = matches.iter().map(|p| p.id.to_string()).collect();
+            Err(SmbError::Validation {
+                message: format!(
+                    "More than one project is named {name_or_id}. Use an id instead: {}.",
+                    ids.join(", ")
+                ),
+                errors: BTreeMap::new(),
+            }
+            .into())
+        }
+    }
+}
+
+impl Tabular for Project {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("ID", 5),
+            ("Name", 20),
+            ("Description", 30),
+            ("Created at", 20),
+            ("Updated at", 20),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.description.clone(),
+            self.created_at.date_naive().to_string(),
+            self.updated_at.date_naive().to_string(),
+        ]
     }
 }
@@ -0,0 +1,248 @@
+use super::cli::BackupCommands;
+use crate::cli::{
+    output::{print_list, print_one, Tabular},
+    prompt, CommandResult,
+};
+use anyhow::Result;
+use console::style;
+use indicatif::HumanBytes;
+use serde_json::json;
+use smbpndk_model::backup::{Backup, BackupPolicy, BackupRestore};
+use smbpndk_networking::client::SmbClient;
+use smbpndk_networking_rdb::{
+    create_backup, delete_backup, get_backup_policy, get_backups, restore_backup,
+    update_backup_policy,
+};
+use spinners::Spinner;
+
+pub async fn process_backup(
+    client: &SmbClient,
+    project_id: &str,
+    commands: BackupCommands,
+) -> Result<CommandResult> {
+    match commands {
+        BackupCommands::Create { name } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Starting a backup...").green().bold().to_string(),
+            );
+            match create_backup(client, project_id, &name).await {
+                Ok(backup) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&backup)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!(
+                            "Backup {} of {name} is {}. Check on it with smb db backup list {name}.",
+                            backup.id, backup.status
+                        ),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        BackupCommands::List { name } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_backups(client, project_id, &name).await {
+                Ok(backups) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let msg = if backups.is_empty() {
+                        format!("{name} has no backups.")
+                    } else {
+                        format!("Showing backups of {name}.")
+                    };
+                    print_list(&backups)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        BackupCommands::Restore {
+            name,
+            id,
+            into,
+            yes,
+        } => {
+            // Restoring into a new database cannot lose data, so it needs no confirmation.
+            let confirmation = into.is_some()
+                || prompt::confirm(
+                    yes,
+                    &format!("Restore backup {id} over {name}? Changes since the backup are lost."),
+                )?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Restoring backup...").green().bold().to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            let restore = BackupRestore { into };
+            match restore_backup(client, project_id, &name, &id, restore).await {
+                Ok(rdb_app) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&rdb_app)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("Backup {id} has been restored into {}.", rdb_app.name),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        BackupCommands::Delete { name, id, yes } => {
+            let confirmation = prompt::confirm(yes, &format!("Delete backup {id} of {name}?"))?;
+
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Deleting backup...").green().bold().to_string(),
+            );
+
+            if !confirmation {
+                return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+            }
+            match delete_backup(client, project_id, &name, &id).await {
+                Ok(_) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("Backup {id} has been deleted."),
+                    )
+                    .with_data(json!({ "name": name, "id": id })))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        BackupCommands::Schedule {
+            name,
+            daily_at,
+            off,
+            retention_days,
+        } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            let result = async {
+                let policy = get_backup_policy(client, project_id, &name).await?;
+                if daily_at.is_none() && !off && retention_days.is_none() {
+                    return Ok((policy, false));
+                }
+                let policy = BackupPolicy {
+                    daily_at: if off {
+                        None
+                    } else {
+                        daily_at.or(policy.daily_at)
+                    },
+                    retention_days: retention_days.unwrap_or(policy.retention_days),
+                };
+                let policy = update_backup_policy(client, project_id, &name, &policy).await?;
+                Ok::<_, anyhow::Error>((policy, true))
+            }
+            .await;
+            match result {
+                Ok((policy, updated)) => {
+                    spinner.stop_and_persist("✅", "Done.".to_owned());
+                    print_one(&policy)?;
+                    let schedule = match &policy.daily_at {
+                        Some(daily_at) => format!("backed up daily at {daily_at} UTC"),
+                        None => "not backed up on a schedule".to_owned(),
+                    };
+                    let msg = format!(
+                        "{name} is {schedule}, backups are kept for {} days.",
+                        policy.retention_days
+                    );
+                    let msg = if updated {
+                        format!("Updated. {msg}")
+                    } else {
+                        msg
+                    };
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        msg,
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+impl Tabular for Backup {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("ID", 6),
+            ("Status", 10),
+            ("Kind", 10),
+            ("Size", 10),
+            ("Created at", 17),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.status.to_string(),
+            if self.scheduled {
+                "scheduled"
+            } else {
+                "manual"
+            }
+            .to_owned(),
+            self.size_bytes
+                .map(|size| HumanBytes(size).to_string())
+                .unwrap_or_default(),
+            self.created_at.format("%Y-%m-%d %H:%M").to_string(),
+        ]
+    }
+}
+
+impl Tabular for BackupPolicy {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![("Daily at (UTC)", 16), ("Retention", 10)]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.daily_at.clone().unwrap_or_else(|| "off".to_owned()),
+            format!("{} days", self.retention_days),
+        ]
+    }
+}
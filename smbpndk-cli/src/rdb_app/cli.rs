@@ -0,0 +1,216 @@
+use clap::Subcommand;
+use smbpndk_model::app_rdb::DataFormat;
+use std::{path::PathBuf, str::FromStr};
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[clap(about = "Add new database.")]
+    New {
+        /// Database name, unique within the project
+        #[clap(short, long)]
+        name: Option<String>,
+        /// Database engine
+        #[clap(short, long, default_value = "postgres", value_parser = ["postgres", "mysql"])]
+        engine: String,
+    },
+
+    #[clap(about = "List all databases of the project.")]
+    List {},
+
+    #[clap(about = "Show detail of a database.")]
+    Show {
+        /// Database name
+        name: String,
+    },
+
+    #[clap(about = "Delete a database and all its data.")]
+    Delete {
+        /// Database name
+        name: String,
+        /// Delete without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(
+        about = "Show how to connect to a database. The password is masked unless --reveal is given.",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Credentials {
+        /// Database name
+        #[clap(required = true)]
+        name: Option<String>,
+        /// Show the password
+        #[clap(long)]
+        reveal: bool,
+
+        #[clap(subcommand)]
+        command: Option<CredentialsCommands>,
+    },
+
+    #[clap(
+        about = "Apply the SQL migrations of a directory, in the order of the numbers their names start with."
+    )]
+    Migrate {
+        /// Directory with the migrations, e.g. 0001_create_users.sql and 0001_create_users.down.sql
+        #[clap(long, global = true, default_value = "migrations")]
+        dir: PathBuf,
+
+        #[clap(subcommand)]
+        command: MigrateCommands,
+    },
+
+    #[clap(about = "Write the data of a database to stdout as SQL or CSV.")]
+    Export {
+        /// Database name
+        name: String,
+        /// sql or csv
+        #[clap(long, default_value = "sql", value_parser = DataFormat::from_str)]
+        format: DataFormat,
+        /// Only this table. Required for csv.
+        #[clap(long, required_if_eq("format", "csv"))]
+        table: Option<String>,
+    },
+
+    #[clap(
+        about = "Load a SQL dump or CSV file into a database. Running it again after it broke off resumes the upload."
+    )]
+    Import {
+        /// Database name
+        name: String,
+        /// File to import
+        file: PathBuf,
+        /// sql or csv. Defaults to the file extension.
+        #[clap(long, value_parser = DataFormat::from_str)]
+        format: Option<DataFormat>,
+        /// Table to load CSV rows into
+        #[clap(long)]
+        table: Option<String>,
+        /// Import without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Back up and restore a database, and schedule backups.")]
+    Backup {
+        #[clap(subcommand)]
+        command: BackupCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CredentialsCommands {
+    #[clap(about = "Replace the password of a database. The old one stops working right away.")]
+    Rotate {
+        /// Database name
+        name: String,
+        /// Show the new password
+        #[clap(long)]
+        reveal: bool,
+        /// Rotate without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MigrateCommands {
+    #[clap(about = "Show which migrations are applied and which are pending.")]
+    Status {
+        /// Database name
+        name: String,
+    },
+
+    #[clap(about = "Apply pending migrations.")]
+    Up {
+        /// Database name
+        name: String,
+        /// Stop after this version
+        #[clap(long)]
+        to: Option<u64>,
+        /// Print the SQL instead of running it
+        #[clap(long)]
+        dry_run: bool,
+        /// Apply even when applied migrations changed or pending ones are older than applied ones
+        #[clap(long)]
+        force: bool,
+    },
+
+    #[clap(about = "Revert the latest applied migrations with their .down.sql files.")]
+    Down {
+        /// Database name
+        name: String,
+        /// How many migrations to revert
+        #[clap(long, default_value_t = 1)]
+        steps: usize,
+        /// Print the SQL instead of running it
+        #[clap(long)]
+        dry_run: bool,
+        /// Revert without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    #[clap(about = "Start a backup of a database.")]
+    Create {
+        /// Database name
+        name: String,
+    },
+
+    #[clap(about = "List backups of a database, newest first.")]
+    List {
+        /// Database name
+        name: String,
+    },
+
+    #[clap(about = "Restore a backup, over the database or into a new one.")]
+    Restore {
+        /// Database name
+        name: String,
+        /// Backup id
+        id: String,
+        /// Restore into a new database with this name and leave the backed up one as it is
+        #[clap(long)]
+        into: Option<String>,
+        /// Restore without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Delete a backup.")]
+    Delete {
+        /// Database name
+        name: String,
+        /// Backup id
+        id: String,
+        /// Delete without confirmation
+        #[clap(short, long)]
+        yes: bool,
+    },
+
+    #[clap(about = "Show or change when a database is backed up and how long backups are kept.")]
+    Schedule {
+        /// Database name
+        name: String,
+        /// Back up every day at this time in UTC, e.g. 03:00
+        #[clap(long, value_parser = parse_time_of_day, conflicts_with = "off")]
+        daily_at: Option<String>,
+        /// Stop scheduled backups
+        #[clap(long)]
+        off: bool,
+        /// Delete backups older than this many days
+        #[clap(long, value_parser = clap::value_parser!(u32).range(1..=365))]
+        retention_days: Option<u32>,
+    },
+}
+
+/// Accepts `HH:MM` and returns it zero padded.
+fn parse_time_of_day(time: &str) -> Result<String, String> {
+    chrono::NaiveTime::parse_from_str(time, "%H:%M")
+        .map(|time| time.format("%H:%M").to_string())
+        .map_err(|_| format!("{time} is not a time like 03:00"))
+}
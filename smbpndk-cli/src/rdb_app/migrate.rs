@@ -0,0 +1,445 @@
+use super::cli::MigrateCommands;
+use crate::cli::{
+    output::{output_format, print_list, OutputFormat, Tabular},
+    prompt, CommandResult,
+};
+use anyhow::{Context, Result};
+use console::style;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use smbpndk_model::app_rdb::{Migration, MigrationDirection, MigrationRun};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use smbpndk_networking_rdb::{get_migrations, run_migration};
+use spinners::Spinner;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A migration file pair in the migrations directory.
+#[derive(Debug)]
+struct LocalMigration {
+    /// As in the file name, e.g. `0001`.
+    version: String,
+    /// What versions are compared by, so that `0001` and `1` are the same migration.
+    number: u64,
+    name: String,
+    up: PathBuf,
+    down: Option<PathBuf>,
+}
+
+/// A row of `smb db migrate status`.
+#[derive(Serialize)]
+struct MigrationStatus {
+    version: String,
+    name: String,
+    /// `applied`, `pending`, `changed` when the file differs from what was applied, or
+    /// `missing` when there is no file for an applied migration.
+    status: &'static str,
+    applied_at: Option<String>,
+}
+
+impl Tabular for MigrationStatus {
+    fn columns() -> Vec<(&'static str, usize)> {
+        vec![
+            ("Version", 16),
+            ("Name", 30),
+            ("Status", 10),
+            ("Applied at", 20),
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.version.clone(),
+            self.name.clone(),
+            self.status.to_owned(),
+            self.applied_at.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+pub async fn process_migrate(
+    client: &SmbClient,
+    project_id: &str,
+    dir: &Path,
+    commands: MigrateCommands,
+) -> Result<CommandResult> {
+    let local = read_migrations(dir)?;
+
+    match commands {
+        MigrateCommands::Status { name } => {
+            let mut spinner = Spinner::new(
+                spinners::Spinners::SimpleDotsScrolling,
+                style("Loading...").green().bold().to_string(),
+            );
+            match get_migrations(client, project_id, &name).await {
+                Ok(applied) => {
+                    spinner.stop_and_persist("✅", "Loaded.".to_owned());
+                    let rows = status(&local, &applied)?;
+                    let pending = rows.iter().filter(|row| row.status == "pending").count();
+                    print_list(&rows)?;
+                    Ok(CommandResult::success(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        format!("{} applied, {pending} pending.", applied.len()),
+                    ))
+                }
+                Err(e) => {
+                    spinner.stop_and_persist("😩", "Failed.".to_owned());
+                    Err(e.into())
+                }
+            }
+        }
+        MigrateCommands::Up {
+            name,
+            to,
+            dry_run,
+            force,
+        } => {
+            let applied = get_migrations(client, project_id, &name).await?;
+            if let Some(to) = to {
+                if !local.iter().any(|migration| migration.number == to) {
+                    return Err(SmbError::NotFound(format!(
+                        "There is no migration {to} in {}.",
+                        dir.display()
+                    ))
+                    .into());
+                }
+            }
+
+            // Applying on top of changed or skipped over migrations gives a schema that no
+            // fresh database would have.
+            let mut problems: Vec<String> = status(&local, &applied)?
+                .into_iter()
+                .filter(|row| row.status == "changed")
+                .map(|row| format!("{} was changed after it was applied", row.version))
+                .collect();
+            let newest_applied = applied
+                .iter()
+                .filter_map(|migration| version_number(&migration.version))
+                .max();
+            let mut runs = Vec::new();
+            for migration in &local {
+                if !is_applied(migration, &applied) {
+                    if let Some(newest) = newest_applied.filter(|&newest| migration.number < newest)
+                    {
+                        problems.push(format!(
+                            "{} is older than {newest}, which is applied already",
+                            migration.version
+                        ));
+                    }
+                    runs.push(migration_run(migration, MigrationDirection::Up)?);
+                }
+                if to == Some(migration.number) {
+                    break;
+                }
+            }
+            if !problems.is_empty() && !force {
+                return Err(SmbError::Validation {
+                    message: "Refusing to migrate. Pass --force to apply the migrations anyway."
+                        .to_owned(),
+                    errors: BTreeMap::from([("migrations".to_owned(), problems)]),
+                }
+                .into());
+            }
+            run_all(client, project_id, &name, runs, dry_run).await
+        }
+        MigrateCommands::Down {
+            name,
+            steps,
+            dry_run,
+            yes,
+        } => {
+            let applied = get_migrations(client, project_id, &name).await?;
+            let mut runs = Vec::new();
+            for migration in applied.iter().rev().take(steps) {
+                let local = find_local(&local, migration)
+                    .ok_or_else(|| missing_file(&migration.version, "there is no file for it"))?;
+                runs.push(migration_run(local, MigrationDirection::Down)?);
+            }
+
+            if !dry_run && !runs.is_empty() {
+                let versions: Vec<&str> = runs.iter().map(|run| run.version.as_str()).collect();
+                let confirmation =
+                    prompt::confirm(yes, &format!("Revert {} on {name}?", versions.join(", ")))?;
+                if !confirmation {
+                    return Ok(CommandResult::cancelled(
+                        Spinner::new(
+                            spinners::Spinners::SimpleDotsScrolling,
+                            style("Loading...").green().bold().to_string(),
+                        ),
+                        "Cancelled.",
+                    ));
+                }
+            }
+            run_all(client, project_id, &name, runs, dry_run).await
+        }
+    }
+}
+
+// Private functions
+
+/// Reads `<version>_<name>.sql` or `.up.sql` files and their `.down.sql` counterparts, in
+/// version order.
+fn read_migrations(dir: &Path) -> Result<Vec<LocalMigration>> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Failed to read {}.", dir.display()))?;
+    let mut ups: BTreeMap<u64, LocalMigration> = BTreeMap::new();
+    let mut downs = BTreeMap::new();
+    let mut problems = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(stem) = file_name.strip_suffix(".sql") else {
+            continue;
+        };
+        let (stem, is_down) = match stem.strip_suffix(".down") {
+            Some(stem) => (stem, true),
+            None => (stem.strip_suffix(".up").unwrap_or(stem), false),
+        };
+        let Some((version, name)) = stem
+            .split_once('_')
+            .filter(|(version, name)| !name.is_empty() && version.parse::<u64>().is_ok())
+        else {
+            problems.push(format!(
+                "{file_name} has to be named like 0001_create_users.sql"
+            ));
+            continue;
+        };
+        let number: u64 = version.parse()?;
+        let same_version = format!("{file_name} has the same version as another file");
+        if is_down {
+            match downs.entry(number) {
+                Entry::Occupied(_) => problems.push(same_version),
+                Entry::Vacant(entry) => {
+                    entry.insert(path);
+                }
+            }
+            continue;
+        }
+        match ups.entry(number) {
+            Entry::Occupied(_) => problems.push(same_version),
+            Entry::Vacant(entry) => {
+                entry.insert(LocalMigration {
+                    version: version.to_owned(),
+                    number,
+                    name: name.to_owned(),
+                    up: path,
+                    down: None,
+                });
+            }
+        }
+    }
+    for (number, down) in downs {
+        match ups.get_mut(&number) {
+            Some(migration) => migration.down = Some(down),
+            None => problems.push(format!("{} has no up migration", down.display())),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(SmbError::Validation {
+            message: format!("Invalid migrations in {}.", dir.display()),
+            errors: BTreeMap::from([("migrations".to_owned(), problems)]),
+        }
+        .into());
+    }
+    Ok(ups.into_values().collect())
+}
+
+fn migration_run(
+    migration: &LocalMigration,
+    direction: MigrationDirection,
+) -> Result<MigrationRun> {
+    let read = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}.", path.display()))
+    };
+    let up = read(&migration.up)?;
+    let sql = match direction {
+        MigrationDirection::Up => up.clone(),
+        MigrationDirection::Down => match &migration.down {
+            Some(down) => read(down)?,
+            None => return Err(missing_file(&migration.version, "it has no .down.sql file")),
+        },
+    };
+    Ok(MigrationRun {
+        version: migration.version.clone(),
+        name: migration.name.clone(),
+        checksum: checksum(&up),
+        direction,
+        sql,
+    })
+}
+
+fn missing_file(version: &str, reason: &str) -> anyhow::Error {
+    SmbError::Validation {
+        message: "Cannot revert.".to_owned(),
+        errors: BTreeMap::from([(
+            "migrations".to_owned(),
+            vec![format!("{version} cannot be reverted, {reason}")],
+        )]),
+    }
+    .into()
+}
+
+fn version_number(version: &str) -> Option<u64> {
+    version.parse().ok()
+}
+
+fn is_applied(local: &LocalMigration, applied: &[Migration]) -> bool {
+    applied
+        .iter()
+        .any(|migration| version_number(&migration.version) == Some(local.number))
+}
+
+/// The file for an applied migration, if there is one.
+fn find_local<'a>(local: &'a [LocalMigration], applied: &Migration) -> Option<&'a LocalMigration> {
+    let number = version_number(&applied.version)?;
+    local.iter().find(|local| local.number == number)
+}
+
+fn checksum(sql: &str) -> String {
+    hex::encode(Sha256::digest(sql.as_bytes()))
+}
+
+fn status(local: &[LocalMigration], applied: &[Migration]) -> Result<Vec<MigrationStatus>> {
+    let mut rows = Vec::new();
+    for migration in applied {
+        let status = match find_local(local, migration) {
+            None => "missing",
+            Some(local) if checksum(&fs::read_to_string(&local.up)?) != migration.checksum => {
+                "changed"
+            }
+            Some(_) => "applied",
+        };
+        rows.push(MigrationStatus {
+            version: migration.version.clone(),
+            name: migration.name.clone(),
+            status,
+            applied_at: Some(migration.applied_at.format("%Y-%m-%d %H:%M").to_string()),
+        });
+    }
+    for migration in local {
+        if !is_applied(migration, applied) {
+            rows.push(MigrationStatus {
+                version: migration.version.clone(),
+                name: migration.name.clone(),
+                status: "pending",
+                applied_at: None,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// Runs the migrations one by one, stopping at the first that fails.
+async fn run_all(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    runs: Vec<MigrationRun>,
+    dry_run: bool,
+) -> Result<CommandResult> {
+    let spinner = || {
+        Spinner::new(
+            spinners::Spinners::SimpleDotsScrolling,
+            style("Loading...").green().bold().to_string(),
+        )
+    };
+    if runs.is_empty() {
+        return Ok(CommandResult::success(spinner(), "Nothing to migrate."));
+    }
+
+    if dry_run {
+        if output_format() == OutputFormat::Table {
+            for run in &runs {
+                let direction = match run.direction {
+                    MigrationDirection::Up => "up",
+                    MigrationDirection::Down => "down",
+                };
+                println!(
+                    "-- {}_{} ({direction})\n{}\n",
+                    run.version,
+                    run.name,
+                    run.sql.trim_end()
+                );
+            }
+        }
+        return Ok(CommandResult::success(
+            spinner(),
+            format!("Dry run, {} migrations would run on {name}.", runs.len()),
+        )
+        .with_data(json!(runs)));
+    }
+
+    let mut done = Vec::new();
+    for run in &runs {
+        let mut spinner = Spinner::new(
+            spinners::Spinners::SimpleDotsScrolling,
+            style(format!("Running {}_{}...", run.version, run.name))
+                .green()
+                .bold()
+                .to_string(),
+        );
+        match run_migration(client, project_id, name, run).await {
+            Ok(_) => {
+                spinner.stop_and_persist("✅", format!("{}_{}", run.version, run.name));
+                done.push(run.version.clone());
+            }
+            Err(e) => {
+                spinner.stop_and_persist("😩", format!("{}_{} failed.", run.version, run.name));
+                return Err(e.into());
+            }
+        }
+    }
+    let verb = match runs[0].direction {
+        MigrationDirection::Up => "Applied",
+        MigrationDirection::Down => "Reverted",
+    };
+    Ok(
+        CommandResult::success(spinner(), format!("{verb} {} migrations.", done.len()))
+            .with_data(json!({ "name": name, "versions": done })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "10_add_index.sql",
+            "2_add_email.up.sql",
+            "2_add_email.down.sql",
+            "1_create_users.sql",
+            "README.md",
+        ] {
+            fs::write(dir.path().join(file), "SELECT 1;").unwrap();
+        }
+        let migrations = read_migrations(dir.path()).unwrap();
+        let versions: Vec<&str> = migrations.iter().map(|m| m.version.as_str()).collect();
+        assert_eq!(versions, ["1", "2", "10"], "numeric order");
+        assert_eq!(migrations[1].name, "add_email");
+        assert!(migrations[1].down.is_some());
+        assert!(migrations[2].down.is_none());
+
+        fs::write(dir.path().join("create_posts.sql"), "").unwrap();
+        fs::write(dir.path().join("3_orphan.down.sql"), "").unwrap();
+        fs::write(dir.path().join("02_drop_email.down.sql"), "").unwrap();
+        let Err(e) = read_migrations(dir.path()) else {
+            panic!("expected invalid migrations");
+        };
+        let Some(SmbError::Validation { errors, .. }) = e.downcast_ref() else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors["migrations"].len(), 3);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::cli::{
+    prompt::{self, MissingInput},
+    CommandResult, Status,
+};
+use anyhow::{anyhow, Context, Result};
+use console::style;
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use smbpndk_model::app_rdb::{DataFormat, ExportCreate, ImportCreate, ImportStatus};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use smbpndk_networking_rdb::{
+    complete_import, create_export, create_import, get_export_range, get_import,
+    upload_import_chunk,
+};
+use spinners::Spinner;
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::Duration,
+};
+
+const CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+/// Attempts per chunk before giving up.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Downloads an export in chunks to stdout, retrying chunks that fail on the way.
+pub async fn process_export(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    format: DataFormat,
+    table: Option<String>,
+) -> Result<CommandResult> {
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style(format!("Exporting {name}..."))
+            .green()
+            .bold()
+            .to_string(),
+    );
+    let export = ExportCreate { format, table };
+    let export = match create_export(client, project_id, name, &export).await {
+        Ok(export) => {
+            spinner.stop_and_persist("✅", "Ready.".to_owned());
+            export
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            return Err(e.into());
+        }
+    };
+
+    let id = export.id.to_string();
+    let progress = progress_bar("Downloading", export.size_bytes)?;
+    let mut out = std::io::stdout().lock();
+    let mut offset = 0;
+    let mut attempts = 0;
+    while offset < export.size_bytes {
+        let end = (offset + CHUNK_SIZE).min(export.size_bytes);
+        match get_export_range(client, project_id, name, &id, offset, end).await {
+            Ok(chunk) if !chunk.is_empty() => {
+                out.write_all(&chunk)?;
+                offset += chunk.len() as u64;
+                progress.set_position(offset);
+                attempts = 0;
+            }
+            Ok(_) => {
+                progress.abandon();
+                return Err(SmbError::Decode("The export ended early.".to_owned()).into());
+            }
+            Err(e) if e.is_transient() && attempts + 1 < MAX_ATTEMPTS => {
+                attempts += 1;
+                progress
+                    .suspend(|| eprintln!("{} {e} Retrying...", style("!").for_stderr().yellow()));
+                tokio::time::sleep(Duration::from_secs(attempts.into())).await;
+            }
+            Err(e) => {
+                progress.abandon();
+                return Err(e.into());
+            }
+        }
+    }
+    out.flush()?;
+    progress.finish_and_clear();
+
+    Ok(CommandResult::success(
+        Spinner::new(
+            spinners::Spinners::SimpleDotsScrolling,
+            style("Loading...").green().bold().to_string(),
+        ),
+        format!("Exported {} of {name}.", HumanBytes(export.size_bytes)),
+    ))
+}
+
+/// Uploads `file` in chunks and loads it into the database. An import of the same file that
+/// broke off earlier is resumed where it stopped.
+pub async fn process_import(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    path: &Path,
+    format: Option<DataFormat>,
+    table: Option<String>,
+    yes: bool,
+) -> Result<CommandResult> {
+    let format = match format {
+        Some(format) => format,
+        None => path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+            .ok_or_else(|| {
+                MissingInput(format!(
+                    "Cannot tell the format of {}. Pass --format sql or --format csv.",
+                    path.display()
+                ))
+            })?,
+    };
+    if format == DataFormat::Csv && table.is_none() {
+        return Err(
+            MissingInput("CSV files need --table to say where the rows go.".to_owned()).into(),
+        );
+    }
+
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to read {}.", path.display()))?;
+    let (size_bytes, checksum) = checksum(&mut file)?;
+
+    let confirmation = prompt::confirm(yes, &format!("Import {} into {name}?", path.display()))?;
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style(format!("Importing into {name}..."))
+            .green()
+            .bold()
+            .to_string(),
+    );
+    if !confirmation {
+        return Ok(CommandResult::cancelled(spinner, "Cancelled."));
+    }
+
+    let import = ImportCreate {
+        format,
+        table,
+        size_bytes,
+        checksum,
+    };
+    let mut import = match create_import(client, project_id, name, &import).await {
+        Ok(import) => {
+            spinner.stop_and_persist("✅", "Ready.".to_owned());
+            import
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            return Err(e.into());
+        }
+    };
+    let id = import.id.to_string();
+    if import.received_bytes > 0 {
+        eprintln!(
+            "Resuming an earlier import at {} of {}.",
+            HumanBytes(import.received_bytes),
+            HumanBytes(size_bytes)
+        );
+    }
+
+    let progress = progress_bar("Uploading", size_bytes)?;
+    progress.set_position(import.received_bytes);
+    let mut attempts = 0;
+    while import.received_bytes < size_bytes {
+        let offset = import.received_bytes;
+        let chunk = read_chunk(&mut file, offset)?;
+        match upload_import_chunk(client, project_id, name, &id, offset, chunk).await {
+            Ok(updated) if updated.received_bytes > offset => {
+                import = updated;
+                progress.set_position(import.received_bytes);
+                attempts = 0;
+            }
+            // The server answered but did not take the chunk, which is no better than failing.
+            Ok(updated) => {
+                attempts += 1;
+                if attempts >= MAX_ATTEMPTS {
+                    progress.abandon();
+                    return Err(anyhow!(
+                        "The server did not take the data at {offset} bytes after {MAX_ATTEMPTS} attempts. Run the import again to continue."
+                    ));
+                }
+                progress.suspend(|| {
+                    eprintln!(
+                        "{} The server did not take the data at {offset} bytes. Retrying...",
+                        style("!").for_stderr().yellow()
+                    )
+                });
+                tokio::time::sleep(Duration::from_secs(attempts.into())).await;
+                import = updated;
+            }
+            Err(e) if e.is_transient() && attempts + 1 < MAX_ATTEMPTS => {
+                attempts += 1;
+                progress
+                    .suspend(|| eprintln!("{} {e} Retrying...", style("!").for_stderr().yellow()));
+                tokio::time::sleep(Duration::from_secs(attempts.into())).await;
+                // The chunk may have arrived even though the response did not.
+                if let Ok(current) = get_import(client, project_id, name, &id).await {
+                    import = current;
+                }
+            }
+            Err(e) => {
+                progress.abandon();
+                return Err(e.into());
+            }
+        }
+    }
+    progress.finish_and_clear();
+
+    let mut spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style(format!("Loading into {name}..."))
+            .green()
+            .bold()
+            .to_string(),
+    );
+    let import = match complete_import(client, project_id, name, &id).await {
+        Ok(import) => {
+            spinner.stop_and_persist("✅", "Done.".to_owned());
+            import
+        }
+        Err(e) => {
+            spinner.stop_and_persist("😩", "Failed.".to_owned());
+            return Err(e.into());
+        }
+    };
+
+    let spinner = Spinner::new(
+        spinners::Spinners::SimpleDotsScrolling,
+        style("Loading...").green().bold().to_string(),
+    );
+    let data = json!({ "name": name, "id": import.id, "status": import.status });
+    let result = match import.status {
+        ImportStatus::Completed => {
+            CommandResult::success(spinner, format!("Imported {} into {name}.", path.display()))
+        }
+        _ => CommandResult::failure(
+            spinner,
+            Status::Failure,
+            format!(
+                "Import {} is {}: {}",
+                import.id,
+                import.status,
+                import.message.as_deref().unwrap_or("no reason was given.")
+            ),
+        ),
+    };
+    Ok(result.with_data(data))
+}
+
+// Private functions
+
+fn progress_bar(action: &str, length: u64) -> Result<ProgressBar> {
+    Ok(ProgressBar::new(length).with_style(
+        ProgressStyle::with_template(&format!(
+            "{action} {{bar:40.green}} {{bytes}}/{{total_bytes}} ({{bytes_per_sec}})"
+        ))?
+        .progress_chars("=> "),
+    ))
+}
+
+/// Size and SHA-256 of the file, read in chunks so big dumps do not have to fit in memory.
+fn checksum(file: &mut File) -> Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+fn read_chunk(file: &mut File, offset: u64) -> Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut chunk = Vec::new();
+    file.take(CHUNK_SIZE).read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use common::{assert_success, stderr, Smb, Stub, GITHUB_CODE_UNLINKED, RESET_CODE, TOKEN};
+use smbpndk_cli::account::lib::process_connect_github;
+use smbpndk_model::account::ErrorCode;
+use smbpndk_networking::client::SmbClient;
+
+#[test]
+fn test_signup() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "signup",
+            "--email",
+            "new@example.com",
+            "--password-stdin",
+        ],
+        "secret123\n",
+    );
+    assert_success(&output);
+    let request = stub.find_request("POST", "v1/users").unwrap();
+    assert_eq!(request.json()["user"]["email"], "new@example.com");
+    assert_eq!(request.json()["user"]["password"], "secret123");
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "signup",
+            "--email",
+            "new@example.com",
+            "--password-stdin",
+        ],
+        "secret123\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Email has already been taken."));
+}
+
+#[test]
+fn test_signup_short_password() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "signup",
+            "--email",
+            "new@example.com",
+            "--password-stdin",
+        ],
+        "short\n",
+    );
+    assert!(!output.status.success());
+    assert!(stub.find_request("POST", "v1/users").is_none());
+}
+
+#[test]
+fn test_login_and_logout() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    stub.add_account("me@example.com", "secret123", true);
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "login",
+            "--email",
+            "me@example.com",
+            "--password-stdin",
+        ],
+        "secret123\n",
+    );
+    assert_success(&output);
+    assert_eq!(
+        std::fs::read_to_string(smb.token_path()).unwrap(),
+        TOKEN,
+        "the token from the Authorization header is stored"
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(smb.token_path())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let output = smb.run(&["account", "logout"]);
+    assert_eq!(
+        output.status.code(),
+        Some(5),
+        "logout needs --yes without input"
+    );
+
+    let output = smb.run(&["account", "logout", "--yes"]);
+    assert_success(&output);
+    let request = stub.find_request("DELETE", "v1/users/sign_out").unwrap();
+    assert_eq!(request.header("authorization"), Some(TOKEN));
+    assert!(!smb.token_path().exists());
+}
+
+#[test]
+fn test_login_failures() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    stub.add_account("me@example.com", "secret123", true);
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "login",
+            "--email",
+            "me@example.com",
+            "--password-stdin",
+        ],
+        "wrong-password\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!smb.token_path().exists());
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "login",
+            "--email",
+            "nobody@example.com",
+            "--password-stdin",
+        ],
+        "secret123\n",
+    );
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = smb.run(&["account", "login", "--email", "me@example.com"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stderr(&output).contains("--password-stdin"));
+}
+
+#[test]
+fn test_login_unverified_email() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    stub.add_account("me@example.com", "secret123", false);
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "login",
+            "--email",
+            "me@example.com",
+            "--password-stdin",
+            "--yes",
+        ],
+        "secret123\n",
+    );
+    assert_success(&output);
+    let request = stub.find_request("POST", "v1/resend_confirmation").unwrap();
+    assert_eq!(request.body, "id=1");
+    assert!(!smb.token_path().exists());
+}
+
+#[test]
+fn test_forgot() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    stub.add_account("me@example.com", "secret123", true);
+
+    let output = smb.run(&["account", "forgot", "--email", "me@example.com"]);
+    assert_success(&output);
+    assert!(stderr(&output).contains("--code"));
+    assert!(stub.find_request("POST", "v1/users/password").is_some());
+
+    let output = smb.run_with_stdin(
+        &[
+            "account",
+            "forgot",
+            "--code",
+            RESET_CODE,
+            "--password-stdin",
+        ],
+        "new-secret\n",
+    );
+    assert_success(&output);
+    let request = stub.find_request("PUT", "v1/users/password").unwrap();
+    assert_eq!(request.json()["user"]["password"], "new-secret");
+
+    let output = smb.run_with_stdin(
+        &["account", "forgot", "--code", "000000", "--password-stdin"],
+        "new-secret\n",
+    );
+    assert_eq!(output.status.code(), Some(5));
+    // The message from the API is shown, not only what was being done.
+    assert!(stderr(&output).contains("reset_password_token is invalid"));
+}
+
+#[tokio::test]
+async fn test_authorize_github() {
+    let stub = Stub::start();
+    let client = SmbClient::new(stub.url().parse().unwrap(), None).unwrap();
+
+    let result = process_connect_github(&client, GITHUB_CODE_UNLINKED.to_owned())
+        .await
+        .unwrap();
+    assert_eq!(result.error_code, Some(ErrorCode::GithubNotLinked));
+    let request = stub.find_request("POST", "v1/authorize").unwrap();
+    assert_eq!(request.body, format!("gh_code={GITHUB_CODE_UNLINKED}"));
+
+    let result = process_connect_github(&client, "unknown".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(result.error_code, Some(ErrorCode::EmailNotFound));
+}
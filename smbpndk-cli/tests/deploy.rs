@@ -0,0 +1,128 @@
+mod common;
+
+use common::{assert_success, stdout, Smb, Stub};
+use serde_json::Value;
+use std::fs;
+
+#[test]
+fn test_deploy() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let project_id = stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+    for name in ["resize", "broken"] {
+        assert_success(&smb.run(&["fun", "new", "--name", name, "--runtime", "nodejs18"]));
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("index.js"), "exports.handler = () => {}").unwrap();
+    fs::write(dir.path().join(".smbignore"), "*.log\n").unwrap();
+    fs::write(dir.path().join("debug.log"), "noise").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = smb.run(&["deploy", "--path", path]);
+    assert_eq!(output.status.code(), Some(5), "the app is required");
+
+    let output = smb.run(&["deploy", "resize", "--path", path, "-o", "json"]);
+    assert_success(&output);
+    let deployment: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(deployment["status"], "ready");
+    let hash = deployment["content_hash"].as_str().unwrap();
+    let archive = format!("v1/projects/{project_id}/fun_apps/resize/archives/{hash}");
+    let upload = stub.find_request("PUT", &archive).unwrap();
+    assert_eq!(upload.header("content-type"), Some("application/gzip"));
+
+    // Ignored files do not change the hash, so nothing is uploaded again.
+    fs::write(dir.path().join("debug.log"), "more noise").unwrap();
+    let output = smb.run(&[
+        "deploy",
+        "resize",
+        "--path",
+        path,
+        "--no-wait",
+        "-o",
+        "json",
+    ]);
+    assert_success(&output);
+    let deployment: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(deployment["content_hash"], hash);
+    assert_eq!(deployment["status"], "pending");
+    let uploads = stub
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == "PUT")
+        .count();
+    assert_eq!(uploads, 1);
+
+    let output = smb.run(&["deploy", "broken", "--path", path]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("npm install exited with 1"));
+}
+
+#[test]
+fn test_deployments_and_rollback() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+    assert_success(&smb.run(&["fun", "new", "--name", "resize", "--runtime", "nodejs18"]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    let mut ids = Vec::new();
+    for version in ["v1", "v2"] {
+        fs::write(dir.path().join("index.js"), version).unwrap();
+        let output = smb.run(&["deploy", "resize", "--path", path, "-o", "json"]);
+        assert_success(&output);
+        let deployment: Value = serde_json::from_str(&stdout(&output)).unwrap();
+        ids.push(deployment["id"].to_string());
+    }
+
+    let output = smb.run(&["deployments", "list"]);
+    assert_eq!(output.status.code(), Some(5), "the app is required");
+    let output = smb.run(&["deployments", "list", "--app", "resize", "-o", "json"]);
+    assert_success(&output);
+    let deployments: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(deployments[0]["id"].to_string(), ids[1], "newest first");
+    assert_eq!(deployments[0]["live"], true);
+    assert_eq!(deployments[0]["author"], "user@example.com");
+    let output = smb.run(&["deployments", "list", "--app", "resize"]);
+    assert!(stdout(&output).contains("ready (live)"));
+
+    let output = smb.run(&["deployments", "show", &ids[1], "--app", "resize"]);
+    assert_success(&output);
+    assert!(stdout(&output).contains("Building"));
+    let output = smb.run(&[
+        "deployments",
+        "show",
+        &ids[1],
+        "--app",
+        "resize",
+        "-o",
+        "json",
+    ]);
+    let deployment: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert!(deployment["logs"].as_str().unwrap().ends_with("Done."));
+
+    let output = smb.run(&["rollback", "--app", "resize"]);
+    assert_eq!(
+        output.status.code(),
+        Some(5),
+        "needs --yes without a prompt"
+    );
+    let output = smb.run(&["rollback", "--app", "resize", "--yes", "-o", "json"]);
+    assert_success(&output);
+    let deployment: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(deployment["id"].to_string(), ids[0]);
+    assert_eq!(deployment["live"], true);
+
+    let output = smb.run(&["rollback", "--app", "resize", "--yes"]);
+    assert_eq!(
+        output.status.code(),
+        Some(1),
+        "nothing older than the first deployment"
+    );
+    assert_success(&smb.run(&["rollback", &ids[1], "--app", "resize", "--yes"]));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{assert_success, stdout, Smb, Stub};
+use serde_json::{json, Value};
+
+#[test]
+fn test_fun_lifecycle() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let project_id = stub.add_project("api", "Public API");
+
+    let output = smb.run(&["fun", "list"]);
+    assert_eq!(output.status.code(), Some(5), "no project in use");
+    assert_success(&smb.run(&["project", "use", "api"]));
+
+    let output = smb.run(&[
+        "fun",
+        "new",
+        "--name",
+        "resize",
+        "--runtime",
+        "nodejs18",
+        "--memory",
+        "256",
+        "-o",
+        "json",
+    ]);
+    assert_success(&output);
+    let created: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(created["memory_mb"], 256);
+
+    let output = smb.run(&[
+        "fun",
+        "new",
+        "--name",
+        "tiny",
+        "--runtime",
+        "nodejs18",
+        "--memory",
+        "64",
+    ]);
+    assert_eq!(output.status.code(), Some(2), "memory is checked locally");
+    let output = smb.run(&["fun", "new", "--name", "resize", "--runtime", "nodejs18"]);
+    assert_eq!(output.status.code(), Some(5), "names are unique");
+
+    let output = smb.run(&["fun", "list"]);
+    assert_success(&output);
+    assert!(stdout(&output).contains("256 MB"));
+
+    assert_success(&smb.run(&["fun", "edit", "resize", "--memory", "512"]));
+    let request = stub
+        .find_request(
+            "PATCH",
+            &format!("v1/projects/{project_id}/fun_apps/resize"),
+        )
+        .unwrap();
+    assert_eq!(request.json(), json!({ "memory_mb": 512 }));
+    assert_eq!(smb.run(&["fun", "edit", "resize"]).status.code(), Some(5));
+
+    let output = smb.run(&["fun", "show", "resize", "-o", "json"]);
+    assert_success(&output);
+    let shown: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown["memory_mb"], 512);
+
+    assert_success(&smb.run(&["fun", "delete", "resize", "--yes"]));
+    assert!(stub.fun_apps().is_empty());
+    assert_eq!(smb.run(&["fun", "show", "resize"]).status.code(), Some(4));
+}
+
+#[test]
+fn test_fun_invoke() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let project_id = stub.add_project("api", "Public API").to_string();
+    let fun = |args: &[&str]| {
+        let mut all = vec!["fun", "--project-id", &project_id];
+        all.extend_from_slice(args);
+        smb.run(&all)
+    };
+    assert_success(&fun(&["new", "--name", "echo", "--runtime", "nodejs18"]));
+
+    let payload = smb.home().join("payload.json");
+    std::fs::write(&payload, r#"{"width": 100}"#).unwrap();
+    let data = format!("@{}", payload.display());
+    let output = fun(&["invoke", "echo", "--data", &data, "-o", "json"]);
+    assert_success(&output);
+    let invocation: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(invocation["response"], json!({ "echo": { "width": 100 } }));
+    assert!(invocation["duration_ms"].is_u64());
+
+    let output = fun(&["invoke", "echo", "--data", r#"{"width": 1}"#]);
+    assert_success(&output);
+    assert!(stdout(&output).contains(r#""width": 1"#));
+
+    let output = fun(&["invoke", "echo", "--data", "{not json"]);
+    assert_eq!(output.status.code(), Some(5));
+    let output = fun(&["invoke", "missing"]);
+    assert_eq!(output.status.code(), Some(4));
+}
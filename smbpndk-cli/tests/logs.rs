@@ -0,0 +1,119 @@
+mod common;
+
+use common::{assert_success, stdout, Smb, Stub};
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader},
+    time::Duration,
+};
+
+#[test]
+fn test_logs() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let project_id = stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+    assert_success(&smb.run(&["fun", "new", "--name", "resize", "--runtime", "nodejs18"]));
+    stub.add_log_line("resize", "info", "resized cat.png");
+    stub.add_log_line("resize", "error", "cat.gif is not an image");
+
+    let output = smb.run(&["logs", "resize"]);
+    assert_success(&output);
+    let out = stdout(&output);
+    assert!(out.contains("INFO  resized cat.png"), "{out}");
+    assert!(out.contains("ERROR cat.gif is not an image"), "{out}");
+
+    let output = smb.run(&[
+        "logs", "resize", "--filter", "gif", "--since", "10m", "-o", "jsonl",
+    ]);
+    assert_success(&output);
+    let lines: Vec<Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["level"], "error");
+    let path = format!("v1/projects/{project_id}/fun_apps/resize/logs");
+    let request = stub
+        .requests()
+        .into_iter()
+        .rfind(|request| request.path == path)
+        .unwrap();
+    assert_eq!(request.query_param("filter").as_deref(), Some("gif"));
+    assert!(request.query_param("since").is_some());
+
+    assert_eq!(
+        smb.run(&["logs", "resize", "--since", "10"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        smb.run(&["logs", "resize", "-o", "csv"]).status.code(),
+        Some(5)
+    );
+    assert_eq!(smb.run(&["logs", "nope"]).status.code(), Some(4));
+}
+
+#[test]
+fn test_logs_follow_resumes() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+    assert_success(&smb.run(&["fun", "new", "--name", "resize", "--runtime", "nodejs18"]));
+    stub.add_log_line("resize", "info", "first");
+
+    let mut child = smb.spawn(&["logs", "resize", "--follow", "-o", "jsonl"]);
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let first: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    assert_eq!(first["message"], "first");
+
+    // The stub closes every stream, so this only shows up after a reconnect.
+    std::thread::sleep(Duration::from_millis(300));
+    stub.add_log_line("resize", "info", "second");
+    let second: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    assert_eq!(second["message"], "second", "nothing is printed twice");
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let resumed = stub
+        .requests()
+        .into_iter()
+        .filter(|request| request.path.ends_with("/logs"))
+        .any(|request| request.header("last-event-id") == Some("0"));
+    assert!(resumed);
+}
+
+#[test]
+fn test_logs_follow_after_cut() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+    assert_success(&smb.run(&["fun", "new", "--name", "resize", "--runtime", "nodejs18"]));
+    stub.add_log_line("resize", "info", "first");
+    stub.add_log_line("resize", "info", "second");
+    stub.cut_next_log_streams(1);
+
+    let mut child = smb.spawn(&["logs", "resize", "--follow", "-o", "jsonl"]);
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let first: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    assert_eq!(first["message"], "first", "the cut event is sent again");
+    let second: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    assert_eq!(second["message"], "second");
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let requests: Vec<_> = stub
+        .requests()
+        .into_iter()
+        .filter(|request| request.path.ends_with("/logs"))
+        .collect();
+    assert_eq!(
+        requests[1].header("last-event-id"),
+        None,
+        "the id of an unfinished event is not resumed from"
+    );
+}
@@ -0,0 +1,322 @@
+mod common;
+
+use common::{assert_success, stdout, Smb, Stub, OAUTH_CODE};
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+#[test]
+fn test_oten_requires_project() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+
+    let output = smb.run(&["oten", "list"]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let output = smb.run(&["oten", "list", "--project-id", "999"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_oten_lifecycle() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+
+    let output = smb.run(&["oten", "new", "--name", "web", "--description", "Web"]);
+    assert_success(&output);
+    let table = stdout(&output);
+    let secret = table
+        .lines()
+        .find_map(|line| line.strip_prefix("Secret: "))
+        .expect("the secret is printed once")
+        .to_owned();
+
+    let output = smb.run(&["oten", "list", "-o", "json"]);
+    assert_success(&output);
+    let apps: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(apps.as_array().unwrap().len(), 1);
+    assert!(apps[0].get("secret").is_none());
+    let id = apps[0]["id"].as_str().unwrap().to_owned();
+
+    assert_success(&smb.run(&["oten", "use", &id]));
+    let config_path = smb.home().join(".smb").join("config.json");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains(&id));
+    assert!(!config.contains("secret-"), "secrets are not stored");
+
+    let output = smb.run(&["oten", "rotate-secret", &id]);
+    assert_eq!(output.status.code(), Some(5), "rotating needs --yes");
+
+    let output = smb.run(&["oten", "rotate-secret", &id, "--yes", "-o", "json"]);
+    assert_success(&output);
+    let rotated: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_ne!(rotated["secret"].as_str().unwrap(), secret);
+
+    assert_success(&smb.run(&["oten", "delete", &id, "--yes"]));
+    assert!(stub.auth_apps().is_empty());
+    let config: Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert!(config["current_auth_app"].is_null());
+    assert_eq!(config["current_project"]["name"], "api");
+
+    let output = smb.run(&["oten", "show", &id]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+/// Creates a project and an app in it. Returns the app id and secret.
+fn create_app(smb: &Smb, stub: &Stub) -> (String, String) {
+    let project_id = stub.add_project("api", "Public API").to_string();
+    let output = smb.run(&[
+        "oten",
+        "new",
+        "--project-id",
+        &project_id,
+        "--name",
+        "web",
+        "--description",
+        "Web",
+        "-o",
+        "json",
+    ]);
+    assert_success(&output);
+    let app: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    (
+        app["id"].as_str().unwrap().to_owned(),
+        app["secret"].as_str().unwrap().to_owned(),
+    )
+}
+
+#[test]
+fn test_oten_try_client_credentials() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let (id, secret) = create_app(&smb, &stub);
+
+    let output = smb.run_with_stdin(
+        &[
+            "oten",
+            "try",
+            &id,
+            "--client-credentials",
+            "--secret-stdin",
+            "-o",
+            "json",
+        ],
+        &format!("{secret}\n"),
+    );
+    assert_success(&output);
+    let inspection: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(inspection["token_type"], "Bearer");
+    assert_eq!(inspection["claims"]["sub"], id.as_str());
+    assert_eq!(inspection["expires_at"], "2100-01-01T00:00:00+00:00");
+    let request = stub.find_request("POST", "v1/oauth/token").unwrap();
+    assert_eq!(request.header("authorization"), None, "no user token");
+    assert!(request.body.contains("grant_type=client_credentials"));
+
+    let output = smb.run_with_stdin(
+        &["oten", "try", &id, "--client-credentials", "--secret-stdin"],
+        "wrong\n",
+    );
+    assert_eq!(output.status.code(), Some(5));
+
+    let output = smb.run(&["oten", "try", &id, "--client-credentials"]);
+    assert_eq!(output.status.code(), Some(5), "the secret is required");
+}
+
+#[test]
+fn test_oten_try_authorization_code() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let (id, secret) = create_app(&smb, &stub);
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+        .to_string();
+
+    let mut child = smb.spawn(&["oten", "try", &id, "--secret-stdin", "--port", &port]);
+    writeln!(child.stdin.take().unwrap(), "{secret}").unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let url = loop {
+        let mut line = String::new();
+        assert!(stderr.read_line(&mut line).unwrap() > 0, "no authorize URL");
+        if let Some(start) = line.find("http://") {
+            break reqwest::Url::parse(line[start..].trim()).unwrap();
+        }
+    };
+    assert_eq!(url.path(), "/v1/oauth/authorize");
+    let client_ids: Vec<String> = url
+        .query_pairs()
+        .filter(|(name, _)| name == "client_id")
+        .map(|(_, value)| value.into_owned())
+        .collect();
+    assert_eq!(client_ids, [id.as_str()], "only the app's client id");
+    assert!(
+        url.query_pairs().all(|(name, _)| name != "client_secret"),
+        "the CLI's secret is not in the browser history"
+    );
+    let state = url
+        .query_pairs()
+        .find(|(name, _)| name == "state")
+        .unwrap()
+        .1
+        .into_owned();
+
+    let mut callback = TcpStream::connect(format!("127.0.0.1:{port}")).unwrap();
+    write!(
+        callback,
+        "GET /callback?code={OAUTH_CODE}&state={state} HTTP/1.1\r\nHost: localhost\r\n\r\n"
+    )
+    .unwrap();
+    let mut page = String::new();
+    callback.read_to_string(&mut page).unwrap();
+    assert!(page.starts_with("HTTP/1.1 200"));
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Access token:"));
+    let request = stub.find_request("POST", "v1/oauth/token").unwrap();
+    assert!(request.body.contains("grant_type=authorization_code"));
+    assert!(request.body.contains(&format!("code={OAUTH_CODE}")));
+    assert_eq!(request.query, "", "the app's credentials are in the body");
+}
+
+#[test]
+fn test_oten_redirect_uris_and_scopes() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let (id, _) = create_app(&smb, &stub);
+    let project_id = stub.projects()[0]["id"].to_string();
+    let oten = |args: &[&str]| {
+        let mut all = vec!["oten", "--project-id", &project_id];
+        all.extend_from_slice(args);
+        smb.run(&all)
+    };
+
+    let output = oten(&[
+        "redirect",
+        "add",
+        &id,
+        "https://example.com/callback",
+        "http://localhost:8809/callback",
+    ]);
+    assert_success(&output);
+    assert_eq!(
+        stub.auth_apps()[0]["redirect_uris"],
+        serde_json::json!([
+            "https://example.com/callback",
+            "http://localhost:8809/callback"
+        ])
+    );
+
+    let patches = |stub: &Stub| {
+        stub.requests()
+            .iter()
+            .filter(|request| request.method == "PATCH")
+            .count()
+    };
+    let output = oten(&[
+        "redirect",
+        "add",
+        &id,
+        "http://example.com/callback",
+        "https://example.com/#token",
+    ]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(patches(&stub), 1, "invalid URIs are not sent");
+
+    let output = oten(&["redirect", "remove", &id, "https://example.com/callback"]);
+    assert_success(&output);
+    let output = oten(&["redirect", "remove", &id, "https://example.com/callback"]);
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = oten(&["redirect", "list", &id, "-o", "json"]);
+    assert_success(&output);
+    let uris: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        uris,
+        serde_json::json!([{ "redirect_uri": "http://localhost:8809/callback" }])
+    );
+
+    assert_success(&oten(&["scopes", "set", &id, "read", "users:write"]));
+    assert_eq!(
+        stub.auth_apps()[0]["scopes"],
+        serde_json::json!(["read", "users:write"])
+    );
+    assert_eq!(
+        oten(&["scopes", "set", &id, "bad\"scope"]).status.code(),
+        Some(5)
+    );
+    assert_eq!(oten(&["scopes", "set", &id]).status.code(), Some(2));
+    assert_success(&oten(&["scopes", "set", &id, "--none"]));
+    assert_eq!(stub.auth_apps()[0]["scopes"], serde_json::json!([]));
+}
+
+#[test]
+fn test_oten_users() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let (app_id, _) = create_app(&smb, &stub);
+    let project_id = stub.projects()[0]["id"].to_string();
+    for n in 0..5 {
+        stub.add_app_user(&app_id, &format!("user{n}@example.com"));
+    }
+    let target = stub
+        .add_app_user(&app_id, "target@corp.example")
+        .to_string();
+    let users = |args: &[&str]| {
+        let mut all = vec![
+            "oten",
+            "users",
+            "--project-id",
+            &project_id,
+            "--app",
+            &app_id,
+        ];
+        all.extend_from_slice(args);
+        smb.run(&all)
+    };
+
+    let output = users(&["list", "--per-page", "4", "--page", "2", "-o", "json"]);
+    assert_success(&output);
+    let page: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(page.as_array().unwrap().len(), 2);
+    let request = stub.requests().pop().unwrap();
+    assert_eq!(request.query_param("page").as_deref(), Some("2"));
+
+    let output = users(&["list", "--per-page", "4", "--all", "-o", "json"]);
+    assert_success(&output);
+    let all: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(all.as_array().unwrap().len(), 6);
+
+    let output = users(&["list", "--email", "corp", "-o", "csv"]);
+    assert_success(&output);
+    assert_eq!(
+        stdout(&output),
+        format!("id,email,disabled,created_at\n{target},target@corp.example,false,2023-03-01T12:00:00.000+00:00\n")
+    );
+
+    assert_success(&users(&["disable", &target]));
+    let output = users(&["show", &target, "-o", "json"]);
+    assert_success(&output);
+    let shown: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown["disabled"], true);
+    assert_success(&users(&["enable", &target]));
+    assert_eq!(stub.app_users()[5]["disabled"], false);
+
+    assert_eq!(users(&["delete", &target]).status.code(), Some(5));
+    assert_success(&users(&["delete", &target, "--yes"]));
+    assert_eq!(stub.app_users().len(), 5);
+    assert_eq!(users(&["show", &target]).status.code(), Some(4));
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use common::{assert_success, stdout, Smb, Stub};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+
+#[test]
+fn test_pkg_publish() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let project_id = stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    fs::write(
+        dir.path().join("resize.sh"),
+        "convert \"$1\" -resize 50% \"$2\"",
+    )
+    .unwrap();
+    let output = smb.run(&["pkg", "publish", "--path", path]);
+    assert_eq!(output.status.code(), Some(1), "the manifest is missing");
+
+    fs::write(
+        dir.path().join("smbpkg.yaml"),
+        "name: image-tools\nversion: \"1.2\"\n",
+    )
+    .unwrap();
+    let output = smb.run(&["pkg", "publish", "--path", path]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a semantic version"));
+
+    fs::write(
+        dir.path().join("smbpkg.yaml"),
+        "name: image-tools\nversion: 1.2.0\ndescription: Resizing\n",
+    )
+    .unwrap();
+    let output = smb.run(&["pkg", "publish", "--path", path, "--dry-run", "-o", "json"]);
+    assert_success(&output);
+    let dry_run: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(dry_run["files"], 2);
+    assert_eq!(dry_run["signed"], false);
+    assert!(stub
+        .requests()
+        .iter()
+        .all(|request| request.method != "PUT"));
+
+    let key = dir.path().join("smbpkg.key");
+    let output = smb.run(&["pkg", "keygen", key.to_str().unwrap(), "-o", "json"]);
+    assert_success(&output);
+    let public_key = serde_json::from_str::<Value>(&stdout(&output)).unwrap()["public_key"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+    assert_eq!(
+        smb.run(&["pkg", "keygen", key.to_str().unwrap()])
+            .status
+            .code(),
+        Some(1),
+        "an existing key is never overwritten"
+    );
+
+    let output = smb.run(&[
+        "pkg",
+        "publish",
+        "--path",
+        path,
+        "--sign-key",
+        key.to_str().unwrap(),
+        "-o",
+        "json",
+    ]);
+    assert_success(&output);
+    let published: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(published["version"], "1.2.0");
+    assert_eq!(published["signed"], true);
+
+    let versions = format!("v1/projects/{project_id}/packages/image-tools/versions");
+    let create = stub.find_request("POST", &versions).unwrap().json();
+    assert_eq!(create["description"], "Resizing");
+    let upload = stub
+        .find_request("PUT", &format!("{versions}/1.2.0/tarball"))
+        .unwrap();
+    assert_eq!(upload.header("content-type"), Some("application/gzip"));
+    let checksum = hex::encode(Sha256::digest(&upload.bytes));
+    assert_eq!(
+        tarball_files(&upload.bytes),
+        ["resize.sh", "smbpkg.yaml"],
+        "the signing key is never published"
+    );
+    assert_eq!(published["checksum"], checksum);
+
+    let signature = &create["signature"];
+    assert_eq!(signature["algorithm"], "ed25519");
+    assert_eq!(signature["public_key"], public_key);
+    let public_key: [u8; 32] = hex::decode(public_key).unwrap().try_into().unwrap();
+    let signature: [u8; 64] = hex::decode(signature["signature"].as_str().unwrap())
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(VerifyingKey::from_bytes(&public_key)
+        .unwrap()
+        .verify(&upload.bytes, &Signature::from_bytes(&signature))
+        .is_ok());
+
+    let output = smb.run(&["pkg", "publish", "--path", path]);
+    assert_eq!(output.status.code(), Some(5), "versions are published once");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already been published"));
+}
+
+#[test]
+fn test_pkg_publish_leaves_keys_out() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let project_id = stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    fs::write(
+        dir.path().join("smbpkg.yaml"),
+        "name: image-tools\nversion: 1.2.0\n",
+    )
+    .unwrap();
+    let key = dir.path().join("release.key");
+    assert_success(&smb.run(&["pkg", "keygen", key.to_str().unwrap()]));
+
+    let output = smb.run(&["pkg", "publish", "--path", path, "-o", "json"]);
+    assert_success(&output);
+    let published: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(published["signed"], false);
+    let upload = stub
+        .find_request(
+            "PUT",
+            &format!("v1/projects/{project_id}/packages/image-tools/versions/1.2.0/tarball"),
+        )
+        .unwrap();
+    assert_eq!(
+        tarball_files(&upload.bytes),
+        ["smbpkg.yaml"],
+        "keys are left out when publishing unsigned"
+    );
+}
+
+#[test]
+fn test_pkg_yank() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    stub.add_project("api", "Public API");
+    assert_success(&smb.run(&["project", "use", "api"]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    fs::write(
+        dir.path().join("smbpkg.yaml"),
+        "name: image-tools\nversion: 1.2.0\n",
+    )
+    .unwrap();
+    assert_success(&smb.run(&["pkg", "publish", "--path", path]));
+
+    let output = smb.run(&["pkg", "yank", "image-tools"]);
+    assert_eq!(output.status.code(), Some(2), "the version is required");
+    let output = smb.run(&["pkg", "yank", "image-tools@1.2.0"]);
+    assert_eq!(output.status.code(), Some(5), "yanking needs confirmation");
+
+    let output = smb.run(&["pkg", "yank", "image-tools@1.2.0", "--yes", "-o", "json"]);
+    assert_success(&output);
+    let yanked: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(yanked["yanked"], true);
+
+    let output = smb.run(&["pkg", "yank", "image-tools@9.9.9", "--yes"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+/// The paths in a gzipped tarball, in the order they were added.
+fn tarball_files(bytes: &[u8]) -> Vec<String> {
+    tar::Archive::new(GzDecoder::new(bytes))
+        .entries()
+        .unwrap()
+        .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+        .collect()
+}
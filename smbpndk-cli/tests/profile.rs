@@ -0,0 +1,18 @@
+mod common;
+
+use common::{assert_success, stderr, stdout, Smb, Stub};
+
+#[test]
+fn test_profile_add_with_profile_flag() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+
+    let output = smb.run(&["--profile", "work", "project", "list"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Create it with smb profile add work."));
+
+    assert_success(&smb.run(&["--profile", "work", "profile", "add", "work"]));
+    let output = smb.run(&["--profile", "work", "profile", "list"]);
+    assert_success(&output);
+    assert!(stdout(&output).contains("work"));
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use common::{assert_success, stdout, Smb, Stub, TOKEN};
+use serde_json::Value;
+
+#[test]
+fn test_project_requires_login() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+
+    let output = smb.run(&["project", "list"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_project_lifecycle() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+
+    let output = smb.run(&[
+        "project",
+        "new",
+        "--name",
+        "api",
+        "--description",
+        "Public API",
+        "-o",
+        "json",
+    ]);
+    assert_success(&output);
+    let created: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(created["name"], "api");
+    let request = stub.find_request("POST", "v1/projects").unwrap();
+    assert_eq!(request.header("authorization"), Some(TOKEN));
+    let id = created["id"].to_string();
+
+    let output = smb.run(&["project", "list", "-o", "json"]);
+    assert_success(&output);
+    let projects: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(projects.as_array().unwrap().len(), 1);
+
+    let output = smb.run(&["project", "show", "--id", &id, "-o", "jsonl"]);
+    assert_success(&output);
+    assert_eq!(stdout(&output).lines().count(), 1);
+
+    let output = smb.run(&["project", "delete", "--id", &id, "--yes"]);
+    assert_success(&output);
+    assert!(stub.projects().is_empty());
+
+    let output = smb.run(&["project", "show", "--id", &id]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_project_validation() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+
+    let output = smb.run(&["project", "new", "--name", "", "--description", "None"]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let output = smb.run(&["project", "new", "--name", "api"]);
+    assert_eq!(output.status.code(), Some(5), "--description is required");
+    assert!(stub.projects().is_empty());
+}
+
+#[test]
+fn test_project_list_formats() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    stub.add_project("api", "Public API");
+    stub.add_project("web", "Website");
+
+    let output = smb.run(&["project", "list"]);
+    assert_success(&output);
+    let table = stdout(&output);
+    assert!(table.starts_with("ID "));
+    assert_eq!(table.lines().count(), 3);
+
+    let output = smb.run(&["project", "list", "-o", "csv"]);
+    assert_success(&output);
+    let csv = stdout(&output);
+    assert!(csv.starts_with("id,name,description,created_at,updated_at\n"));
+    assert_eq!(csv.lines().count(), 3);
+
+    let output = smb.run(&["project", "list", "-o", "yaml"]);
+    assert_success(&output);
+    assert!(stdout(&output).contains("name: web"));
+}
+
+#[test]
+fn test_project_edit() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let id = stub.add_project("api", "Public API").to_string();
+
+    let output = smb.run(&["project", "edit", "--id", &id, "--name", "gateway"]);
+    assert_success(&output);
+    let request = stub
+        .find_request("PATCH", &format!("v1/projects/{id}"))
+        .unwrap();
+    assert_eq!(request.json(), serde_json::json!({ "name": "gateway" }));
+    assert_eq!(stub.projects()[0]["name"], "gateway");
+    assert_eq!(stub.projects()[0]["description"], "Public API");
+
+    let output = smb.run(&["project", "edit", "--id", &id]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let output = smb.run(&["project", "edit", "--id", "999", "--name", "gone"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_project_by_name() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let id = stub.add_project("api", "Public API");
+    stub.add_project("web", "Website");
+    stub.add_project("web", "Website again");
+
+    let output = smb.run(&["project", "show", "api", "-o", "json"]);
+    assert_success(&output);
+    let project: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(project["id"], id);
+
+    let output = smb.run(&["project", "show", &id.to_string(), "-o", "json"]);
+    assert_success(&output);
+
+    let output = smb.run(&["project", "show", "web"]);
+    assert_eq!(output.status.code(), Some(5), "two projects are named web");
+
+    let output = smb.run(&["project", "show", "nope"]);
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = smb.run(&["project", "show"]);
+    assert_eq!(output.status.code(), Some(5), "no picker without input");
+
+    let output = smb.run(&["project", "delete", "api", "--yes"]);
+    assert_success(&output);
+    assert_eq!(stub.projects().len(), 2);
+}
+
+#[test]
+fn test_project_current() {
+    let stub = Stub::start();
+    let smb = Smb::new(&stub);
+    smb.login(&stub);
+    let id = stub.add_project("api", "Public API");
+    let config_path = smb.home().join(".smb").join("config.json");
+    std::fs::write(
+        &config_path,
+        r#"{"current_project": null, "current_auth_app": {"id": "app", "secret": null, "name": "app", "created_at": "2023-03-01T12:00:00.000+00:00", "updated_at": "2023-03-01T12:00:00.000+00:00"}}"#,
+    )
+    .unwrap();
+
+    let output = smb.run(&["project", "current"]);
+    assert_eq!(output.status.code(), Some(4));
+
+    assert_success(&smb.run(&["project", "use", "api"]));
+    let config: Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["current_project"]["id"], id);
+    assert_eq!(
+        config["current_auth_app"]["id"], "app",
+        "other context is kept"
+    );
+
+    let output = smb.run(&["project", "current", "-o", "json"]);
+    assert_success(&output);
+    let current: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(current["name"], "api");
+
+    let output = smb.run(&["project", "show", "-o", "json"]);
+    assert_success(&output);
+    let shown: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown["id"], id, "show defaults to the current project");
+
+    assert_success(&smb.run(&["project", "edit", "--description", "Gateway"]));
+    assert_eq!(stub.projects()[0]["description"], "Gateway");
+
+    assert_success(&smb.run(&["project", "unuse"]));
+    let output = smb.run(&["project", "current"]);
+    assert_eq!(output.status.code(), Some(4));
+    let config: Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["current_auth_app"]["id"], "app");
+}
@@ -1,6 +1,5 @@
 use crate::signup::GithubEmail;
-use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 
 // SMBPNDK Users.
@@ -39,13 +38,50 @@ pub struct SmbAuthorization {
     pub error_code: Option<ErrorCode>,
 }
 
-#[derive(Debug, Serialize_repr, Deserialize_repr, PartialEq)]
-#[repr(u32)]
+/// Account error codes sent by the API in `error_code`.
+///
+/// Codes this version does not know about yet deserialize to `Unknown`.
+#[derive(Debug, PartialEq)]
 pub enum ErrorCode {
-    EmailNotFound = 1000,
-    EmailUnverified = 1001,
-    PasswordNotSet = 1003,
-    GithubNotLinked = 1004,
+    EmailNotFound,
+    EmailUnverified,
+    PasswordNotSet,
+    GithubNotLinked,
+    Unknown(u32),
+}
+
+impl ErrorCode {
+    pub fn code(&self) -> u32 {
+        match self {
+            ErrorCode::EmailNotFound => 1000,
+            ErrorCode::EmailUnverified => 1001,
+            ErrorCode::PasswordNotSet => 1003,
+            ErrorCode::GithubNotLinked => 1004,
+            ErrorCode::Unknown(code) => *code,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            1000 => ErrorCode::EmailNotFound,
+            1001 => ErrorCode::EmailUnverified,
+            1003 => ErrorCode::PasswordNotSet,
+            1004 => ErrorCode::GithubNotLinked,
+            code => ErrorCode::Unknown(code),
+        }
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(ErrorCode::from_code)
+    }
 }
 
 impl Display for ErrorCode {
@@ -55,6 +91,7 @@ impl Display for ErrorCode {
             ErrorCode::EmailUnverified => write!(f, "Email not verified."),
             ErrorCode::PasswordNotSet => write!(f, "Password not set."),
             ErrorCode::GithubNotLinked => write!(f, "Github not connected."),
+            ErrorCode::Unknown(code) => write!(f, "Unknown error code {code}."),
         }
     }
 }
@@ -101,4 +138,18 @@ mod tests {
         });
         assert_eq!(serde_json::to_value(smb_authorization).unwrap(), json);
     }
+
+    #[test]
+    fn test_unknown_error_code() {
+        let json = json!({
+            "message": "test",
+            "user": null,
+            "user_email": null,
+            "user_info": null,
+            "error_code": 1999,
+        });
+        let smb_authorization: SmbAuthorization = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(smb_authorization.error_code, Some(ErrorCode::Unknown(1999)));
+        assert_eq!(serde_json::to_value(smb_authorization).unwrap(), json);
+    }
 }
@@ -5,8 +5,16 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AuthApp {
     pub id: String,
+    /// Only sent when the app is created or its secret is rotated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub secret: Option<String>,
     pub name: String,
+    /// Where the authorization server may send the user back to. Matched exactly.
+    #[serde(default)]
+    pub redirect_uris: Vec<String>,
+    /// Scopes the app is allowed to ask for.
+    #[serde(default)]
+    pub scopes: Vec<String>,
     #[serde(with = "ar_date_format")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "ar_date_format")]
@@ -17,6 +25,82 @@ pub struct AuthApp {
 pub struct AuthAppCreate {
     pub name: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_uris: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+}
+
+/// Changes to an auth app. Lists replace the current ones, fields left as `None` are not sent.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AuthAppUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uris: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
+/// An end user who signed up through an auth app.
+///
+/// Repeats the fields of [`User`](crate::account::User) instead of flattening it, as CSV
+/// output cannot write flattened fields.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AppUser {
+    pub id: i32,
+    pub email: String,
+    /// Disabled users cannot sign in and their tokens stop working.
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(with = "ar_date_format")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query for listing the users of an auth app.
+#[derive(Serialize, Debug)]
+pub struct AppUserQuery {
+    pub page: u32,
+    pub per_page: u32,
+    /// Only users whose email contains this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// OAuth grants an app can use to get a token.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantType {
+    AuthorizationCode,
+    ClientCredentials,
+}
+
+/// Form body for the token endpoint. `code` and `redirect_uri` are only sent for
+/// the authorization code grant.
+#[derive(Serialize, Debug)]
+pub struct TokenRequest {
+    pub grant_type: GrantType,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// A successful token response, as in RFC 6749 section 5.1.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OauthToken {
+    pub access_token: String,
+    pub token_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 #[cfg(test)]
@@ -28,6 +112,8 @@ mod tests {
         let auth_app_create = AuthAppCreate {
             name: "test".to_owned(),
             description: "test".to_owned(),
+            redirect_uris: vec![],
+            scopes: vec![],
         };
         let json = json!({
             "name": "test",
@@ -35,4 +121,61 @@ mod tests {
         });
         assert_eq!(serde_json::to_value(auth_app_create).unwrap(), json);
     }
+
+    #[test]
+    fn test_auth_app() {
+        let json = json!({
+            "id": "app",
+            "name": "test",
+            "redirect_uris": ["https://example.com/callback"],
+            "scopes": [],
+            "created_at": "2023-03-01T12:00:00.000+00:00",
+            "updated_at": "2023-03-01T12:00:00.000+00:00",
+        });
+        let auth_app: AuthApp = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(auth_app.secret, None);
+        assert_eq!(serde_json::to_value(auth_app).unwrap(), json);
+    }
+
+    #[test]
+    fn test_auth_app_update() {
+        let auth_app_update = AuthAppUpdate {
+            scopes: Some(vec!["read".to_owned()]),
+            ..Default::default()
+        };
+        let json = json!({ "scopes": ["read"] });
+        assert_eq!(serde_json::to_value(auth_app_update).unwrap(), json);
+    }
+
+    #[test]
+    fn test_app_user() {
+        let json = json!({
+            "id": 7,
+            "email": "user@example.com",
+            "disabled": true,
+            "created_at": "2023-03-01T12:00:00.000+00:00",
+        });
+        let app_user: AppUser = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(app_user.email, "user@example.com");
+        assert!(app_user.disabled);
+        assert_eq!(serde_json::to_value(app_user).unwrap(), json);
+    }
+
+    #[test]
+    fn test_token_request() {
+        let token_request = TokenRequest {
+            grant_type: GrantType::ClientCredentials,
+            client_id: "app".to_owned(),
+            client_secret: "secret".to_owned(),
+            code: None,
+            redirect_uri: None,
+            scope: None,
+        };
+        let json = json!({
+            "grant_type": "client_credentials",
+            "client_id": "app",
+            "client_secret": "secret",
+        });
+        assert_eq!(serde_json::to_value(token_request).unwrap(), json);
+    }
 }
@@ -0,0 +1,85 @@
+use crate::ar_date_format;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A function app. Its name is unique within the project.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FunApp {
+    pub id: i32,
+    pub name: String,
+    /// e.g. `nodejs18` or `python3.11`.
+    pub runtime: String,
+    pub memory_mb: u32,
+    #[serde(with = "ar_date_format")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "ar_date_format")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FunAppCreate {
+    pub name: String,
+    pub runtime: String,
+    pub memory_mb: u32,
+}
+
+/// Settings to change on a function app. Missing fields are left as they are.
+#[derive(Serialize, Debug)]
+pub struct FunAppUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u32>,
+}
+
+/// A line a function app logged, sent as the data of a server-sent event.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LogLine {
+    #[serde(with = "ar_date_format")]
+    pub timestamp: DateTime<Utc>,
+    /// e.g. `info` or `error`, as the runtime reported it.
+    pub level: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LogQuery {
+    /// Only lines logged after this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only lines containing this text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// Keep the stream open for new lines.
+    pub follow: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn test_fun_app_create() {
+        let fun_app_create = FunAppCreate {
+            name: "resize".to_owned(),
+            runtime: "nodejs18".to_owned(),
+            memory_mb: 256,
+        };
+        let json = json!({
+            "name": "resize",
+            "runtime": "nodejs18",
+            "memory_mb": 256,
+        });
+        assert_eq!(serde_json::to_value(fun_app_create).unwrap(), json);
+    }
+
+    #[test]
+    fn test_fun_app_update() {
+        let fun_app_update = FunAppUpdate {
+            runtime: None,
+            memory_mb: Some(512),
+        };
+        let json = json!({ "memory_mb": 512 });
+        assert_eq!(serde_json::to_value(fun_app_update).unwrap(), json);
+    }
+}
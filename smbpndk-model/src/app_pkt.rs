@@ -0,0 +1,63 @@
+use crate::ar_date_format;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A published version of a package in the registry of a project.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageVersion {
+    pub name: String,
+    pub version: String,
+    /// SHA-256 of the tarball, hex encoded.
+    pub checksum: String,
+    pub size_bytes: u64,
+    /// Published with a signature over the tarball.
+    #[serde(default)]
+    pub signed: bool,
+    /// Yanked versions stay downloadable but are no longer picked for new installs.
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(with = "ar_date_format")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A version to publish. The tarball is uploaded after, and must match `checksum`.
+#[derive(Serialize, Debug)]
+pub struct PackageVersionCreate {
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub checksum: String,
+    pub size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackageSignature>,
+}
+
+/// An Ed25519 signature over the tarball and the key to check it with, both hex encoded.
+#[derive(Serialize, Debug)]
+pub struct PackageSignature {
+    pub algorithm: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn test_package_version_create() {
+        let package_version = PackageVersionCreate {
+            version: "1.2.0".to_owned(),
+            description: None,
+            checksum: "ab".repeat(32),
+            size_bytes: 1024,
+            signature: None,
+        };
+        let json = json!({
+            "version": "1.2.0",
+            "checksum": "ab".repeat(32),
+            "size_bytes": 1024,
+        });
+        assert_eq!(serde_json::to_value(package_version).unwrap(), json);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::ar_date_format;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A backup of a database.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Backup {
+    pub id: i32,
+    pub status: BackupStatus,
+    /// Taken by the schedule rather than by hand.
+    #[serde(default)]
+    pub scheduled: bool,
+    /// Known once the backup completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(with = "ar_date_format")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupStatus {
+    Pending,
+    Completed,
+    Failed,
+    /// A status this version does not know about yet.
+    #[serde(other)]
+    Unknown,
+}
+
+impl Display for BackupStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            BackupStatus::Pending => "pending",
+            BackupStatus::Completed => "completed",
+            BackupStatus::Failed => "failed",
+            BackupStatus::Unknown => "unknown",
+        };
+        write!(f, "{status}")
+    }
+}
+
+/// When a database is backed up on its own and for how long backups are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BackupPolicy {
+    /// Time of day in UTC, as `HH:MM`. No scheduled backups when missing.
+    pub daily_at: Option<String>,
+    /// Backups older than this are deleted, scheduled or not.
+    pub retention_days: u32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BackupRestore {
+    /// Name of a new database to restore into. Restores over the backed up one when missing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub into: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn test_backup() {
+        let json = json!({
+            "id": 1,
+            "status": "pending",
+            "created_at": "2023-03-01T12:00:00.000+00:00",
+        });
+        let backup: Backup = serde_json::from_value(json).unwrap();
+        assert_eq!(backup.status, BackupStatus::Pending);
+        assert!(!backup.scheduled);
+        assert_eq!(backup.size_bytes, None);
+    }
+
+    #[test]
+    fn test_backup_policy() {
+        let policy = BackupPolicy {
+            daily_at: None,
+            retention_days: 7,
+        };
+        let json = json!({ "daily_at": null, "retention_days": 7 });
+        assert_eq!(serde_json::to_value(policy).unwrap(), json);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::ar_date_format;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A deployment of a function app.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Deployment {
+    pub id: i32,
+    pub status: DeploymentStatus,
+    /// Email of whoever deployed it.
+    pub author: String,
+    /// Whether this deployment is serving the app.
+    #[serde(default)]
+    pub live: bool,
+    /// Hash of the uploaded files, see `smb deploy`.
+    pub content_hash: String,
+    /// Why the deployment failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(with = "ar_date_format")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "ar_date_format")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentStatus {
+    Pending,
+    Building,
+    Ready,
+    Failed,
+    /// A status this version does not know about yet.
+    #[serde(other)]
+    Unknown,
+}
+
+impl DeploymentStatus {
+    /// Ready and failed deployments do not change anymore.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, DeploymentStatus::Ready | DeploymentStatus::Failed)
+    }
+}
+
+impl Display for DeploymentStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            DeploymentStatus::Pending => "pending",
+            DeploymentStatus::Building => "building",
+            DeploymentStatus::Ready => "ready",
+            DeploymentStatus::Failed => "failed",
+            DeploymentStatus::Unknown => "unknown",
+        };
+        write!(f, "{status}")
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct DeploymentCreate {
+    pub content_hash: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn test_deployment() {
+        let json = json!({
+            "id": 1,
+            "status": "rolling_back",
+            "author": "user@example.com",
+            "content_hash": "abc",
+            "created_at": "2023-03-01T12:00:00.000+00:00",
+            "updated_at": "2023-03-01T12:00:00.000+00:00",
+        });
+        let deployment: Deployment = serde_json::from_value(json).unwrap();
+        assert_eq!(deployment.status, DeploymentStatus::Unknown);
+        assert!(!deployment.status.is_finished());
+        assert_eq!(deployment.message, None);
+        assert!(!deployment.live);
+    }
+}
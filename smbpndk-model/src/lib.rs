@@ -1,15 +1,23 @@
 pub mod account;
 pub mod app_auth;
+pub mod app_fun;
+pub mod app_pkt;
+pub mod app_rdb;
+pub mod backup;
+pub mod deployment;
 pub mod forgot;
 pub mod login;
+pub mod page;
 pub mod project;
 pub mod signup;
 
 pub mod ar_date_format {
-    use chrono::{DateTime, TimeZone, Utc};
+    use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Utc};
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f%#z";
+    // Written to config files by older versions.
+    const NAIVE_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
 
     // The signature of a serialize_with function must follow the pattern:
     //
@@ -22,7 +30,7 @@ pub mod ar_date_format {
     where
         S: Serializer,
     {
-        let s = format!("{}", date.naive_utc());
+        let s = date.to_rfc3339_opts(SecondsFormat::Millis, false);
         serializer.serialize_str(&s)
     }
 
@@ -39,6 +47,11 @@ pub mod ar_date_format {
     {
         let s = String::deserialize(deserializer)?;
         Utc.datetime_from_str(&s, FORMAT)
+            .or_else(|e| {
+                NaiveDateTime::parse_from_str(&s, NAIVE_FORMAT)
+                    .map(|date| Utc.from_utc_datetime(&date))
+                    .map_err(|_| e)
+            })
             .map_err(serde::de::Error::custom)
     }
 
@@ -53,5 +66,24 @@ pub mod ar_date_format {
             let json = json!("2020-01-01T00:00:00Z");
             assert_eq!(serde_json::to_value(date.unwrap()).unwrap(), json);
         }
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Dated {
+            #[serde(with = "super")]
+            date: DateTime<Utc>,
+        }
+
+        #[test]
+        fn test_ar_date_format_roundtrip() {
+            let date = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+            let json = serde_json::to_value(Dated { date }).unwrap();
+            assert_eq!(json, json!({ "date": "2020-01-01T00:00:00.000+00:00" }));
+            let dated: Dated = serde_json::from_value(json).unwrap();
+            assert_eq!(dated.date, date);
+
+            let dated: Dated =
+                serde_json::from_value(json!({ "date": "2020-01-01 00:00:00" })).unwrap();
+            assert_eq!(dated.date, date);
+        }
     }
 }
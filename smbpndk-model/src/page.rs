@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// One page of a paginated list. Pages start at 1.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    /// Number of pages. An empty list still has its first page.
+    pub fn pages(&self) -> u64 {
+        if self.per_page == 0 {
+            return 1;
+        }
+        self.total.div_ceil(u64::from(self.per_page)).max(1)
+    }
+
+    pub fn is_last(&self) -> bool {
+        u64::from(self.page) >= self.pages()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    #[test]
+    fn test_page() {
+        let json = json!({
+            "items": [1, 2],
+            "page": 2,
+            "per_page": 2,
+            "total": 5,
+        });
+        let page: Page<i32> = serde_json::from_value(json).unwrap();
+        assert_eq!(page.pages(), 3);
+        assert!(!page.is_last());
+
+        let empty: Page<i32> = Page {
+            items: vec![],
+            page: 1,
+            per_page: 20,
+            total: 0,
+        };
+        assert_eq!(empty.pages(), 1);
+        assert!(empty.is_last());
+    }
+}
@@ -1,14 +1,18 @@
 use crate::{app_auth::AuthApp, ar_date_format};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct Config {
     pub current_project: Option<Project>,
     pub current_auth_app: Option<AuthApp>,
+    /// API environment: production, staging, local or a custom URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Serialize)]
+#[derive(Deserialize, Debug, Serialize, Clone)]
 pub struct Project {
     pub id: i32,
     pub name: String,
@@ -18,12 +22,28 @@ pub struct Project {
     #[serde(with = "ar_date_format")]
     pub updated_at: DateTime<Utc>,
 }
+
+impl Display for Project {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.id)
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct ProjectCreate {
     pub name: String,
     pub description: String,
 }
 
+/// Fields to change on a project. Missing fields are left as they are.
+#[derive(Serialize, Debug)]
+pub struct ProjectUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,4 +60,16 @@ mod tests {
         });
         assert_eq!(serde_json::to_value(project_create).unwrap(), json);
     }
+
+    #[test]
+    fn test_project_update() {
+        let project_update = ProjectUpdate {
+            name: Some("test".to_owned()),
+            description: None,
+        };
+        let json = json!({
+            "name": "test",
+        });
+        assert_eq!(serde_json::to_value(project_update).unwrap(), json);
+    }
 }
@@ -0,0 +1,354 @@
+use log::debug;
+use smbpndk_model::{
+    app_fun::{FunApp, FunAppCreate, FunAppUpdate, LogQuery},
+    deployment::{Deployment, DeploymentCreate},
+};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+
+const PATH_PROJECTS: &str = "v1/projects";
+const PATH_FUN_APPS: &str = "fun_apps";
+const PATH_INVOKE: &str = "invoke";
+const PATH_ARCHIVES: &str = "archives";
+const PATH_DEPLOYMENTS: &str = "deployments";
+const PATH_LOGS: &str = "logs";
+const PATH_PROMOTE: &str = "promote";
+
+/// Header to resume a stream of server-sent events after the last one received.
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+pub async fn get_fun_apps(client: &SmbClient, project_id: &str) -> Result<Vec<FunApp>, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_FUN_APPS])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let fun_apps: Vec<FunApp> = response.json().await?;
+            Ok(fun_apps)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn create_fun_app(
+    client: &SmbClient,
+    project_id: &str,
+    fun_app: FunAppCreate,
+) -> Result<FunApp, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_FUN_APPS])
+        .json(&fun_app)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::CREATED => {
+            let fun_app: FunApp = response.json().await?;
+            Ok(fun_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_fun_app(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<FunApp, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_FUN_APPS, name])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let fun_app: FunApp = response.json().await?;
+            Ok(fun_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn update_fun_app(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    fun_app: FunAppUpdate,
+) -> Result<FunApp, SmbError> {
+    let response = client
+        .patch(&[PATH_PROJECTS, project_id, PATH_FUN_APPS, name])
+        .json(&fun_app)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let fun_app: FunApp = response.json().await?;
+            Ok(fun_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn delete_fun_app(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<(), SmbError> {
+    let response = client
+        .delete(&[PATH_PROJECTS, project_id, PATH_FUN_APPS, name])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            debug!("Fun app deleted.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Runs the function with `payload` as its JSON input and returns whatever it responded.
+pub async fn invoke_fun_app(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    payload: Vec<u8>,
+) -> Result<String, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_FUN_APPS, name, PATH_INVOKE])
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => Ok(response.text().await?),
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Opens the log stream of the app. The response body is a stream of server-sent events, each
+/// with a `LogLine` as JSON data, to be read as it comes.
+pub async fn get_fun_app_logs(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    query: &LogQuery,
+    last_event_id: Option<&str>,
+) -> Result<reqwest::Response, SmbError> {
+    let mut request = client
+        .get(&[PATH_PROJECTS, project_id, PATH_FUN_APPS, name, PATH_LOGS])
+        .query(query)
+        .header(reqwest::header::ACCEPT, "text/event-stream");
+    if let Some(last_event_id) = last_event_id {
+        request = request.header(LAST_EVENT_ID, last_event_id);
+    }
+    let response = request.send().await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => Ok(response),
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Whether an archive with this content hash was uploaded before, so it can be deployed again
+/// without uploading it.
+pub async fn archive_exists(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    content_hash: &str,
+) -> Result<bool, SmbError> {
+    let response = client
+        .request(
+            reqwest::Method::HEAD,
+            &[
+                PATH_PROJECTS,
+                project_id,
+                PATH_FUN_APPS,
+                name,
+                PATH_ARCHIVES,
+                content_hash,
+            ],
+        )
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => Ok(true),
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Uploads a gzipped tarball of the app, stored under its content hash.
+pub async fn upload_archive(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    content_hash: &str,
+    length: u64,
+    archive: impl Into<reqwest::Body>,
+) -> Result<(), SmbError> {
+    let response = client
+        .put(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_FUN_APPS,
+            name,
+            PATH_ARCHIVES,
+            content_hash,
+        ])
+        .header(reqwest::header::CONTENT_TYPE, "application/gzip")
+        .header(reqwest::header::CONTENT_LENGTH, length)
+        .body(archive)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK | reqwest::StatusCode::CREATED => {
+            debug!("Archive uploaded.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn create_deployment(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    deployment: DeploymentCreate,
+) -> Result<Deployment, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_FUN_APPS,
+            name,
+            PATH_DEPLOYMENTS,
+        ])
+        .json(&deployment)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::CREATED => {
+            let deployment: Deployment = response.json().await?;
+            Ok(deployment)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_deployment(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+) -> Result<Deployment, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_FUN_APPS,
+            name,
+            PATH_DEPLOYMENTS,
+            id,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let deployment: Deployment = response.json().await?;
+            Ok(deployment)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Deployments of the app, newest first.
+pub async fn get_deployments(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<Vec<Deployment>, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_FUN_APPS,
+            name,
+            PATH_DEPLOYMENTS,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let deployments: Vec<Deployment> = response.json().await?;
+            Ok(deployments)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// The build output of a deployment as plain text.
+pub async fn get_deployment_logs(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+) -> Result<String, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_FUN_APPS,
+            name,
+            PATH_DEPLOYMENTS,
+            id,
+            PATH_LOGS,
+        ])
+        .header(reqwest::header::ACCEPT, "text/plain")
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => Ok(response.text().await?),
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Makes a ready deployment the live one, without building it again.
+pub async fn promote_deployment(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+) -> Result<Deployment, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_FUN_APPS,
+            name,
+            PATH_DEPLOYMENTS,
+            id,
+            PATH_PROMOTE,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let deployment: Deployment = response.json().await?;
+            Ok(deployment)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
@@ -0,0 +1,330 @@
+use log::debug;
+use reqwest::{Method, Url};
+use serde::Deserialize;
+use smbpndk_model::{
+    app_auth::{
+        AppUser, AppUserQuery, AuthApp, AuthAppCreate, AuthAppUpdate, OauthToken, TokenRequest,
+    },
+    page::Page,
+};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+use std::collections::BTreeMap;
+
+const PATH_PROJECTS: &str = "v1/projects";
+const PATH_AUTH_APPS: &str = "auth_apps";
+const PATH_ROTATE_SECRET: &str = "rotate_secret";
+const PATH_USERS: &str = "users";
+const PATH_DISABLE: &str = "disable";
+const PATH_ENABLE: &str = "enable";
+const PATH_OAUTH_AUTHORIZE: &str = "v1/oauth/authorize";
+const PATH_OAUTH_TOKEN: &str = "v1/oauth/token";
+
+pub async fn get_auth_apps(client: &SmbClient, project_id: &str) -> Result<Vec<AuthApp>, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_AUTH_APPS])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let auth_apps: Vec<AuthApp> = response.json().await?;
+            Ok(auth_apps)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// The response carries the app secret. It is not returned again after this.
+pub async fn create_auth_app(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app: AuthAppCreate,
+) -> Result<AuthApp, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_AUTH_APPS])
+        .json(&auth_app)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::CREATED => {
+            let auth_app: AuthApp = response.json().await?;
+            Ok(auth_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_auth_app(
+    client: &SmbClient,
+    project_id: &str,
+    id: &str,
+) -> Result<AuthApp, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_AUTH_APPS, id])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let auth_app: AuthApp = response.json().await?;
+            Ok(auth_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Lists in `auth_app` replace the ones the app has.
+pub async fn update_auth_app(
+    client: &SmbClient,
+    project_id: &str,
+    id: &str,
+    auth_app: AuthAppUpdate,
+) -> Result<AuthApp, SmbError> {
+    let response = client
+        .patch(&[PATH_PROJECTS, project_id, PATH_AUTH_APPS, id])
+        .json(&auth_app)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let auth_app: AuthApp = response.json().await?;
+            Ok(auth_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn delete_auth_app(
+    client: &SmbClient,
+    project_id: &str,
+    id: &str,
+) -> Result<(), SmbError> {
+    let response = client
+        .delete(&[PATH_PROJECTS, project_id, PATH_AUTH_APPS, id])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            debug!("Auth app deleted.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Replaces the app secret. The old one stops working and the new one is only in this response.
+pub async fn rotate_secret(
+    client: &SmbClient,
+    project_id: &str,
+    id: &str,
+) -> Result<AuthApp, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_AUTH_APPS,
+            id,
+            PATH_ROTATE_SECRET,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let auth_app: AuthApp = response.json().await?;
+            Ok(auth_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_app_users(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    query: &AppUserQuery,
+) -> Result<Page<AppUser>, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_AUTH_APPS,
+            auth_app_id,
+            PATH_USERS,
+        ])
+        .query(query)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let users: Page<AppUser> = response.json().await?;
+            Ok(users)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_app_user(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    id: &str,
+) -> Result<AppUser, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_AUTH_APPS,
+            auth_app_id,
+            PATH_USERS,
+            id,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let user: AppUser = response.json().await?;
+            Ok(user)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Disabling a user also revokes the tokens issued to them.
+pub async fn set_app_user_disabled(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    id: &str,
+    disabled: bool,
+) -> Result<AppUser, SmbError> {
+    let action = if disabled { PATH_DISABLE } else { PATH_ENABLE };
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_AUTH_APPS,
+            auth_app_id,
+            PATH_USERS,
+            id,
+            action,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let user: AppUser = response.json().await?;
+            Ok(user)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn delete_app_user(
+    client: &SmbClient,
+    project_id: &str,
+    auth_app_id: &str,
+    id: &str,
+) -> Result<(), SmbError> {
+    let response = client
+        .delete(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_AUTH_APPS,
+            auth_app_id,
+            PATH_USERS,
+            id,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            debug!("App user deleted.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// The page to send the user to for the authorization code grant.
+pub fn authorize_url(
+    client: &SmbClient,
+    client_id: &str,
+    redirect_uri: &str,
+    scope: Option<&str>,
+    state: &str,
+) -> Result<Url, SmbError> {
+    let mut params = vec![
+        ("response_type", "code"),
+        ("client_id", client_id),
+        ("redirect_uri", redirect_uri),
+        ("state", state),
+    ];
+    if let Some(scope) = scope {
+        params.push(("scope", scope));
+    }
+    // Opened in the browser, so it must not carry the CLI's own credentials.
+    Url::parse_with_params(
+        &client.url_without_credentials(&[PATH_OAUTH_AUTHORIZE]),
+        params,
+    )
+    .map_err(|e| SmbError::Decode(e.to_string()))
+}
+
+/// Exchanges a grant for tokens. Use a client without a user token, the app
+/// authenticates with its own credentials.
+pub async fn request_token(
+    client: &SmbClient,
+    token_request: &TokenRequest,
+) -> Result<OauthToken, SmbError> {
+    let response = client
+        .request_without_credentials(Method::POST, &[PATH_OAUTH_TOKEN])
+        .form(token_request)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let token: OauthToken = response.json().await?;
+            Ok(token)
+        }
+        // Bad grants and bad client credentials come back as an OAuth error, not
+        // as our usual error envelope.
+        status @ (reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNAUTHORIZED) => {
+            let body = response.text().await?;
+            match serde_json::from_str::<OauthError>(&body) {
+                Ok(error) => Err(SmbError::Validation {
+                    message: error.message(),
+                    errors: BTreeMap::new(),
+                }),
+                Err(_) => Err(SmbError::from_parts(status, None, &body)),
+            }
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+// Private functions
+
+/// RFC 6749 section 5.2.
+#[derive(Deserialize)]
+struct OauthError {
+    error: String,
+    error_description: Option<String>,
+}
+
+impl OauthError {
+    fn message(&self) -> String {
+        match &self.error_description {
+            Some(description) => format!("{}: {description}", self.error),
+            None => self.error.clone(),
+        }
+    }
+}
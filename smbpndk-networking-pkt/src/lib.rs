@@ -0,0 +1,102 @@
+use log::debug;
+use smbpndk_model::app_pkt::{PackageVersion, PackageVersionCreate};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+
+const PATH_PROJECTS: &str = "v1/projects";
+const PATH_PACKAGES: &str = "packages";
+const PATH_VERSIONS: &str = "versions";
+const PATH_TARBALL: &str = "tarball";
+const PATH_YANK: &str = "yank";
+
+/// Registers a version of a package, creating the package on its first version. Until its
+/// tarball is uploaded, the same version can be created again with the same checksum.
+pub async fn create_package_version(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    package_version: PackageVersionCreate,
+) -> Result<PackageVersion, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_PACKAGES,
+            name,
+            PATH_VERSIONS,
+        ])
+        .json(&package_version)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK | reqwest::StatusCode::CREATED => {
+            let package_version: PackageVersion = response.json().await?;
+            Ok(package_version)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Uploads the gzipped tarball of a version. The server checks it against the checksum the
+/// version was created with.
+pub async fn upload_package_tarball(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    version: &str,
+    length: u64,
+    tarball: impl Into<reqwest::Body>,
+) -> Result<PackageVersion, SmbError> {
+    let response = client
+        .put(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_PACKAGES,
+            name,
+            PATH_VERSIONS,
+            version,
+            PATH_TARBALL,
+        ])
+        .header(reqwest::header::CONTENT_TYPE, "application/gzip")
+        .header(reqwest::header::CONTENT_LENGTH, length)
+        .body(tarball)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK | reqwest::StatusCode::CREATED => {
+            debug!("Package tarball uploaded.");
+            let package_version: PackageVersion = response.json().await?;
+            Ok(package_version)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn yank_package_version(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    version: &str,
+) -> Result<PackageVersion, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_PACKAGES,
+            name,
+            PATH_VERSIONS,
+            version,
+            PATH_YANK,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let package_version: PackageVersion = response.json().await?;
+            Ok(package_version)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
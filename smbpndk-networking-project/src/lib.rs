@@ -1,44 +1,29 @@
-use anyhow::{anyhow, Result};
 use log::debug;
-use reqwest::Client;
 use smbpndk_model::{
     self,
-    project::{Project, ProjectCreate},
+    project::{Project, ProjectCreate, ProjectUpdate},
 };
-use smbpndk_networking::{get_smb_token, smb_base_url_builder};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
 
-pub async fn get_all() -> Result<Vec<Project>> {
-    // Get current token
-    let token = get_smb_token().await?;
+const PATH_PROJECTS: &str = "v1/projects";
 
-    debug!("Current token: {}", token);
-
-    let response = Client::new()
-        .get(build_project_url())
-        .header("Authorization", token)
-        .header("User-agent", "smbpndk-cli")
-        .send()
-        .await?;
+pub async fn get_all(client: &SmbClient) -> Result<Vec<Project>, SmbError> {
+    let response = client.get(&[PATH_PROJECTS]).send().await?;
 
     match response.status() {
         reqwest::StatusCode::OK => {
             let projects: Vec<Project> = response.json().await?;
             Ok(projects)
         }
-        _ => Err(anyhow!("Failed to fetch projects.")),
+        _ => Err(SmbError::from_response(response).await),
     }
 }
 
-pub async fn create_project(project: ProjectCreate) -> Result<Project> {
-    // Get current token
-    let token = get_smb_token().await?;
-
-    let response = Client::new()
-        .post(build_project_url())
-        .json(&project)
-        .header("Authorization", token)
-        .send()
-        .await?;
+pub async fn create_project(
+    client: &SmbClient,
+    project: ProjectCreate,
+) -> Result<Project, SmbError> {
+    let response = client.post(&[PATH_PROJECTS]).json(&project).send().await?;
 
     match response.status() {
         reqwest::StatusCode::CREATED => {
@@ -46,19 +31,12 @@ pub async fn create_project(project: ProjectCreate) -> Result<Project> {
             // println!("Project created: {project:#?}");
             Ok(project)
         }
-        _ => Err(anyhow!("Failed to create a project.")),
+        _ => Err(SmbError::from_response(response).await),
     }
 }
 
-pub async fn get_project(id: String) -> Result<Project> {
-    // Get current token
-    let token = get_smb_token().await?;
-
-    let response = Client::new()
-        .get(build_project_url_with_id(id))
-        .header("Authorization", token)
-        .send()
-        .await?;
+pub async fn get_project(client: &SmbClient, id: String) -> Result<Project, SmbError> {
+    let response = client.get(&[PATH_PROJECTS, &id]).send().await?;
 
     match response.status() {
         reqwest::StatusCode::OK => {
@@ -66,40 +44,38 @@ pub async fn get_project(id: String) -> Result<Project> {
             //println!("Project requested: {project:#?}");
             Ok(project)
         }
-        _ => Err(anyhow!("Failed to request a project.")),
+        _ => Err(SmbError::from_response(response).await),
     }
 }
 
-pub async fn delete_project(id: String) -> Result<()> {
-    // Get current token
-    let token = get_smb_token().await?;
-
-    let response = Client::new()
-        .delete(build_project_url_with_id(id))
-        .header("Authorization", token)
+pub async fn update_project(
+    client: &SmbClient,
+    id: String,
+    project: ProjectUpdate,
+) -> Result<Project, SmbError> {
+    let response = client
+        .patch(&[PATH_PROJECTS, &id])
+        .json(&project)
         .send()
         .await?;
 
     match response.status() {
         reqwest::StatusCode::OK => {
-            debug!("Project deleted.");
-            Ok(())
+            let project: Project = response.json().await?;
+            Ok(project)
         }
-        _ => Err(anyhow!("Failed to delete a project.")),
+        _ => Err(SmbError::from_response(response).await),
     }
 }
 
-// Private functions
-
-fn build_project_url() -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route("v1/projects");
-    url_builder.build()
-}
+pub async fn delete_project(client: &SmbClient, id: String) -> Result<(), SmbError> {
+    let response = client.delete(&[PATH_PROJECTS, &id]).send().await?;
 
-fn build_project_url_with_id(id: String) -> String {
-    let mut url_builder = smb_base_url_builder();
-    url_builder.add_route("v1/projects");
-    url_builder.add_route(id.as_str());
-    url_builder.build()
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            debug!("Project deleted.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
 }
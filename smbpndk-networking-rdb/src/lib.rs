@@ -0,0 +1,516 @@
+use log::debug;
+use smbpndk_model::{
+    app_rdb::{
+        Export, ExportCreate, Import, ImportCreate, Migration, MigrationRun, RdbApp, RdbAppCreate,
+        RdbCredentials,
+    },
+    backup::{Backup, BackupPolicy, BackupRestore},
+};
+use smbpndk_networking::{client::SmbClient, error::SmbError};
+
+const PATH_PROJECTS: &str = "v1/projects";
+const PATH_RDB_APPS: &str = "rdb_apps";
+const PATH_CREDENTIALS: &str = "credentials";
+const PATH_ROTATE: &str = "rotate";
+const PATH_MIGRATIONS: &str = "migrations";
+const PATH_BACKUPS: &str = "backups";
+const PATH_BACKUP_POLICY: &str = "backup_policy";
+const PATH_RESTORE: &str = "restore";
+const PATH_EXPORTS: &str = "exports";
+const PATH_IMPORTS: &str = "imports";
+const PATH_DATA: &str = "data";
+const PATH_CHUNKS: &str = "chunks";
+const PATH_COMPLETE: &str = "complete";
+
+pub async fn get_rdb_apps(client: &SmbClient, project_id: &str) -> Result<Vec<RdbApp>, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_RDB_APPS])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let rdb_apps: Vec<RdbApp> = response.json().await?;
+            Ok(rdb_apps)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn create_rdb_app(
+    client: &SmbClient,
+    project_id: &str,
+    rdb_app: RdbAppCreate,
+) -> Result<RdbApp, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_RDB_APPS])
+        .json(&rdb_app)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::CREATED => {
+            let rdb_app: RdbApp = response.json().await?;
+            Ok(rdb_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_rdb_app(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<RdbApp, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_RDB_APPS, name])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let rdb_app: RdbApp = response.json().await?;
+            Ok(rdb_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn delete_rdb_app(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<(), SmbError> {
+    let response = client
+        .delete(&[PATH_PROJECTS, project_id, PATH_RDB_APPS, name])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            debug!("Rdb app deleted.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_rdb_credentials(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<RdbCredentials, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_CREDENTIALS,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let credentials: RdbCredentials = response.json().await?;
+            Ok(credentials)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Replaces the password. The old one stops working right away.
+pub async fn rotate_rdb_credentials(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<RdbCredentials, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_CREDENTIALS,
+            PATH_ROTATE,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let credentials: RdbCredentials = response.json().await?;
+            Ok(credentials)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Migrations applied to the database, oldest first.
+pub async fn get_migrations(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<Vec<Migration>, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_MIGRATIONS,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let migrations: Vec<Migration> = response.json().await?;
+            Ok(migrations)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Applies or reverts one migration and returns its record.
+pub async fn run_migration(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    migration: &MigrationRun,
+) -> Result<Migration, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_MIGRATIONS,
+        ])
+        .json(migration)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK | reqwest::StatusCode::CREATED => {
+            let migration: Migration = response.json().await?;
+            Ok(migration)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Backups of the database, newest first.
+pub async fn get_backups(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<Vec<Backup>, SmbError> {
+    let response = client
+        .get(&[PATH_PROJECTS, project_id, PATH_RDB_APPS, name, PATH_BACKUPS])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let backups: Vec<Backup> = response.json().await?;
+            Ok(backups)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Starts a backup. It is pending until the platform finished it.
+pub async fn create_backup(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<Backup, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_RDB_APPS, name, PATH_BACKUPS])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::CREATED => {
+            let backup: Backup = response.json().await?;
+            Ok(backup)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn delete_backup(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+) -> Result<(), SmbError> {
+    let response = client
+        .delete(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_BACKUPS,
+            id,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            debug!("Backup deleted.");
+            Ok(())
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Restores a backup and returns the database it was restored into.
+pub async fn restore_backup(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+    restore: BackupRestore,
+) -> Result<RdbApp, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_BACKUPS,
+            id,
+            PATH_RESTORE,
+        ])
+        .json(&restore)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK | reqwest::StatusCode::CREATED => {
+            let rdb_app: RdbApp = response.json().await?;
+            Ok(rdb_app)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_backup_policy(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+) -> Result<BackupPolicy, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_BACKUP_POLICY,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let policy: BackupPolicy = response.json().await?;
+            Ok(policy)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn update_backup_policy(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    policy: &BackupPolicy,
+) -> Result<BackupPolicy, SmbError> {
+    let response = client
+        .put(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_BACKUP_POLICY,
+        ])
+        .json(policy)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let policy: BackupPolicy = response.json().await?;
+            Ok(policy)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Takes a snapshot of the data to download with `get_export_range`.
+pub async fn create_export(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    export: &ExportCreate,
+) -> Result<Export, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_RDB_APPS, name, PATH_EXPORTS])
+        .json(export)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::CREATED => {
+            let export: Export = response.json().await?;
+            Ok(export)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Downloads the bytes `start..end` of an export.
+pub async fn get_export_range(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+    start: u64,
+    end: u64,
+) -> Result<Vec<u8>, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_EXPORTS,
+            id,
+            PATH_DATA,
+        ])
+        .header(reqwest::header::ACCEPT, "application/octet-stream")
+        .header(reqwest::header::RANGE, format!("bytes={start}-{}", end - 1))
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::PARTIAL_CONTENT => Ok(response.bytes().await?.to_vec()),
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Starts an import, or returns the unfinished one of the same file.
+pub async fn create_import(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    import: &ImportCreate,
+) -> Result<Import, SmbError> {
+    let response = client
+        .post(&[PATH_PROJECTS, project_id, PATH_RDB_APPS, name, PATH_IMPORTS])
+        .json(import)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK | reqwest::StatusCode::CREATED => {
+            let import: Import = response.json().await?;
+            Ok(import)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+pub async fn get_import(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+) -> Result<Import, SmbError> {
+    let response = client
+        .get(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_IMPORTS,
+            id,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let import: Import = response.json().await?;
+            Ok(import)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Uploads the chunk starting at `offset`, which has to be the `received_bytes` of the import.
+pub async fn upload_import_chunk(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+    offset: u64,
+    chunk: Vec<u8>,
+) -> Result<Import, SmbError> {
+    let response = client
+        .put(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_IMPORTS,
+            id,
+            PATH_CHUNKS,
+        ])
+        .query(&[("offset", offset)])
+        .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+        .body(chunk)
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let import: Import = response.json().await?;
+            Ok(import)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
+
+/// Loads the uploaded file into the database.
+pub async fn complete_import(
+    client: &SmbClient,
+    project_id: &str,
+    name: &str,
+    id: &str,
+) -> Result<Import, SmbError> {
+    let response = client
+        .post(&[
+            PATH_PROJECTS,
+            project_id,
+            PATH_RDB_APPS,
+            name,
+            PATH_IMPORTS,
+            id,
+            PATH_COMPLETE,
+        ])
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let import: Import = response.json().await?;
+            Ok(import)
+        }
+        _ => Err(SmbError::from_response(response).await),
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{environment::Environment, get_smb_token, smb_base_url_builder};
+use anyhow::Result;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT},
+    Client, Method, RequestBuilder,
+};
+use url_builder::URLBuilder;
+
+const USER_AGENT: &str = concat!("smbpndk-cli/", env!("CARGO_PKG_VERSION"));
+
+/// A client for the SmbPndk API.
+///
+/// It owns a single connection pool, so create it once and reuse it for every call.
+#[derive(Debug, Clone)]
+pub struct SmbClient {
+    http: Client,
+    environment: Environment,
+    token: Option<String>,
+}
+
+impl SmbClient {
+    /// Creates a client for the given environment, optionally authorized with `token`.
+    pub fn new(environment: Environment, token: Option<String>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+
+        let http = Client::builder()
+            .user_agent(USER_AGENT)
+            .default_headers(headers)
+            .build()?;
+
+        Ok(Self {
+            http,
+            environment,
+            token,
+        })
+    }
+
+    /// Creates a client authorized with the token of the logged in user, if there is one.
+    pub async fn from_token_file(environment: Environment) -> Result<Self> {
+        Self::new(environment, get_smb_token().await.ok())
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token;
+    }
+
+    /// Builds the full URL for the given routes, e.g. `["v1/projects", "1"]`.
+    pub fn url(&self, routes: &[&str]) -> String {
+        let mut url_builder = smb_base_url_builder(&self.environment);
+        for route in routes {
+            url_builder.add_route(route);
+        }
+        url_builder.build()
+    }
+
+    /// Like [`SmbClient::url`], but without the CLI's own client credentials. For URLs that are
+    /// shown to the user or that carry the credentials of another app, like OAuth.
+    pub fn url_without_credentials(&self, routes: &[&str]) -> String {
+        let mut url_builder = URLBuilder::new();
+        url_builder
+            .set_protocol(self.environment.protocol())
+            .set_host(self.environment.host());
+        for route in routes {
+            url_builder.add_route(route);
+        }
+        url_builder.build()
+    }
+
+    /// Starts a request to the given routes. The token is attached when the client has one.
+    pub fn request(&self, method: Method, routes: &[&str]) -> RequestBuilder {
+        self.request_to(method, self.url(routes))
+    }
+
+    /// Starts a request to [`SmbClient::url_without_credentials`].
+    pub fn request_without_credentials(&self, method: Method, routes: &[&str]) -> RequestBuilder {
+        self.request_to(method, self.url_without_credentials(routes))
+    }
+
+    pub fn get(&self, routes: &[&str]) -> RequestBuilder {
+        self.request(Method::GET, routes)
+    }
+
+    pub fn post(&self, routes: &[&str]) -> RequestBuilder {
+        self.request(Method::POST, routes)
+    }
+
+    pub fn put(&self, routes: &[&str]) -> RequestBuilder {
+        self.request(Method::PUT, routes)
+    }
+
+    pub fn patch(&self, routes: &[&str]) -> RequestBuilder {
+        self.request(Method::PATCH, routes)
+    }
+
+    pub fn delete(&self, routes: &[&str]) -> RequestBuilder {
+        self.request(Method::DELETE, routes)
+    }
+
+    fn request_to(&self, method: Method, url: String) -> RequestBuilder {
+        let request = self.http.request(method, url);
+        match &self.token {
+            Some(token) => request.header("Authorization", token),
+            None => request,
+        }
+    }
+}
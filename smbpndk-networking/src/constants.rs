@@ -1,9 +1,8 @@
-pub const TOKEN_PATH_STR: &str = ".smb/token";
+pub const TOKEN_FILE_NAME: &str = "token";
 
-#[cfg(debug_assertions)]
-pub const GH_OAUTH_CLIENT_ID: &str = "053e152f1b78ecee552b";
-#[cfg(not(debug_assertions))]
-pub const GH_OAUTH_CLIENT_ID: &str = "bf1f12d97659a6495e43";
+// GitHub OAuth apps. Production has its own app, every other environment uses the development one.
+pub const GH_OAUTH_CLIENT_ID_PRODUCTION: &str = "bf1f12d97659a6495e43";
+pub const GH_OAUTH_CLIENT_ID_DEVELOPMENT: &str = "053e152f1b78ecee552b";
 
 pub const GH_OAUTH_REDIRECT_HOST: &str = "http://localhost";
 pub const GH_OAUTH_REDIRECT_PORT: &str = "8808";
@@ -11,15 +10,11 @@ pub const GH_OAUTH_REDIRECT_PORT: &str = "8808";
 pub const SMB_CLIENT_ID: &str = "cli";
 pub const SMB_CLIENT_SECRET: &str = "secretttttttt";
 
-#[cfg(debug_assertions)]
-pub const SMB_API_PROTOCOL: &str = "http";
-#[cfg(not(debug_assertions))]
-pub const SMB_API_PROTOCOL: &str = "https";
-
-#[cfg(debug_assertions)]
-pub const SMB_API_HOST: &str = "localhost:8088";
-#[cfg(not(debug_assertions))]
-pub const SMB_API_HOST: &str = "api.smbpndk.com";
+// API environments
+pub const SMB_API_URL_PRODUCTION: &str = "https://api.smbpndk.com";
+pub const SMB_API_URL_STAGING: &str = "https://api.staging.smbpndk.com";
+pub const SMB_API_URL_LOCAL: &str = "http://localhost:8088";
+pub const SMB_API_URL_ENV: &str = "SMB_API_URL";
 
 // Paths
 pub const PATH_USERS: &str = "v1/users";
@@ -0,0 +1,221 @@
+use crate::smb_token_path;
+use anyhow::{anyhow, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use log::debug;
+use smbpndk_utils::profile::{create_private_dir, smb_dir};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+pub const SMB_TOKEN_PASSPHRASE_ENV: &str = "SMB_TOKEN_PASSPHRASE";
+
+const ENCRYPTED_PREFIX: &str = "smbenc:v1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Somewhere to keep the session token between commands.
+pub trait CredentialStore {
+    /// Returns the stored token, or `None` when there is none.
+    fn load(&self) -> Result<Option<String>>;
+    fn save(&self, token: &str) -> Result<()>;
+    fn delete(&self) -> Result<()>;
+}
+
+/// Stores the token in a file only the current user can access.
+///
+/// The token is encrypted at rest when a passphrase is given.
+pub struct FileCredentialStore {
+    path: PathBuf,
+    passphrase: Option<String>,
+}
+
+impl FileCredentialStore {
+    pub fn new(path: PathBuf, passphrase: Option<String>) -> Self {
+        Self { path, passphrase }
+    }
+
+    /// The token file of the active profile, encrypted with `SMB_TOKEN_PASSPHRASE` if it is set.
+    pub fn for_active_profile() -> Result<Self> {
+        let passphrase = std::env::var(SMB_TOKEN_PASSPHRASE_ENV)
+            .ok()
+            .filter(|passphrase| !passphrase.is_empty());
+        Ok(Self::new(smb_token_path()?, passphrase))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl CredentialStore for FileCredentialStore {
+    fn load(&self) -> Result<Option<String>> {
+        if !self.path.is_file() {
+            return Ok(None);
+        }
+        check_private(&self.path)?;
+
+        let contents = fs::read_to_string(&self.path)?;
+        match contents.strip_prefix(ENCRYPTED_PREFIX) {
+            Some(encrypted) => match &self.passphrase {
+                Some(passphrase) => decrypt(encrypted.trim(), passphrase).map(Some),
+                None => Err(anyhow!(
+                    "The token is encrypted. Set {SMB_TOKEN_PASSPHRASE_ENV} to read it."
+                )),
+            },
+            None => Ok(Some(contents)),
+        }
+    }
+
+    fn save(&self, token: &str) -> Result<()> {
+        let contents = match &self.passphrase {
+            Some(passphrase) => format!("{ENCRYPTED_PREFIX}{}", encrypt(token, passphrase)?),
+            None => token.to_owned(),
+        };
+
+        let dir = self
+            .path
+            .parent()
+            .ok_or_else(|| anyhow!("Invalid token path: {}", self.path.display()))?;
+        create_private_dir(dir)?;
+        // Named profiles live under ~/.smb, which has to be private as well.
+        if let Ok(smb_dir) = smb_dir() {
+            if dir.starts_with(&smb_dir) && dir != smb_dir {
+                create_private_dir(&smb_dir)?;
+            }
+        }
+
+        // Write to a temporary file first, so a crash never leaves a half written token.
+        let tmp_path = dir.join(format!(".token.{}.tmp", std::process::id()));
+        let mut options = OpenOptions::new();
+        options.create(true).write(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp_path, &self.path).map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            anyhow!("Failed to save token: {e}")
+        })?;
+        debug!("Token saved to {}.", self.path.display());
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+// Private functions
+
+#[cfg(unix)]
+fn check_private(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(anyhow!(
+            "Refusing to read the token at {}: it is readable by other users. Run chmod 600 on it or login again.",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_private(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive the token key: {e}"))?;
+    Ok(key)
+}
+
+fn encrypt(token: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), token.as_bytes())
+        .map_err(|_| anyhow!("Failed to encrypt the token."))?;
+
+    let mut bytes = salt.to_vec();
+    bytes.extend_from_slice(&nonce);
+    bytes.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(bytes))
+}
+
+fn decrypt(encoded: &str, passphrase: &str) -> Result<String> {
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|_| anyhow!("The stored token is corrupted. Please login again."))?;
+    if bytes.len() < SALT_LEN + NONCE_LEN {
+        return Err(anyhow!(
+            "The stored token is corrupted. Please login again."
+        ));
+    }
+    let (salt, rest) = bytes.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let token = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Failed to decrypt the token. Is the passphrase right?"))?;
+    Ok(String::from_utf8(token)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_encrypted_token_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        let store = FileCredentialStore::new(path.clone(), Some("secret".to_owned()));
+        store.save("Bearer abc").unwrap();
+
+        assert!(!fs::read_to_string(&path).unwrap().contains("abc"));
+        assert_eq!(store.load().unwrap(), Some("Bearer abc".to_owned()));
+
+        let wrong = FileCredentialStore::new(path.clone(), Some("wrong".to_owned()));
+        assert!(wrong.load().is_err());
+        let plain = FileCredentialStore::new(path, None);
+        assert!(plain.load().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_refuse_readable_token() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        let store = FileCredentialStore::new(path.clone(), None);
+        store.save("Bearer abc").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(store.load().is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::constants::{
+    GH_OAUTH_CLIENT_ID_DEVELOPMENT, GH_OAUTH_CLIENT_ID_PRODUCTION, SMB_API_URL_ENV,
+    SMB_API_URL_LOCAL, SMB_API_URL_PRODUCTION, SMB_API_URL_STAGING,
+};
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// The SmbPndk API backend the CLI talks to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Environment {
+    Production,
+    Staging,
+    Local,
+    /// Any other backend, e.g. `http://127.0.0.1:3000`.
+    Custom(String),
+}
+
+impl Environment {
+    /// Resolves the environment in order of precedence: the `--api-url` flag, the `--env` flag,
+    /// the `SMB_API_URL` environment variable, the config entry and finally the build default.
+    pub fn resolve(api_url: Option<&str>, env: Option<&str>, config: Option<&str>) -> Result<Self> {
+        let from_env_var = std::env::var(SMB_API_URL_ENV).ok();
+        let selected = api_url
+            .or(env)
+            .or(from_env_var.as_deref())
+            .or(config)
+            .filter(|value| !value.trim().is_empty());
+
+        let environment = match selected {
+            Some(value) => value.parse()?,
+            None => Self::default(),
+        };
+        debug!("Using API environment: {environment}.");
+        Ok(environment)
+    }
+
+    pub fn base_url(&self) -> &str {
+        match self {
+            Self::Production => SMB_API_URL_PRODUCTION,
+            Self::Staging => SMB_API_URL_STAGING,
+            Self::Local => SMB_API_URL_LOCAL,
+            Self::Custom(url) => url,
+        }
+    }
+
+    pub fn protocol(&self) -> &str {
+        self.base_url()
+            .split_once("://")
+            .map(|(protocol, _)| protocol)
+            .unwrap_or("https")
+    }
+
+    /// The host including the port and any path prefix, e.g. `localhost:8088`.
+    pub fn host(&self) -> &str {
+        let url = self.base_url();
+        url.split_once("://").map(|(_, host)| host).unwrap_or(url)
+    }
+
+    pub fn github_client_id(&self) -> &'static str {
+        match self {
+            Self::Production => GH_OAUTH_CLIENT_ID_PRODUCTION,
+            _ => GH_OAUTH_CLIENT_ID_DEVELOPMENT,
+        }
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Local
+        } else {
+            Self::Production
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let value = s.trim();
+        match value.to_lowercase().as_str() {
+            "production" | "prod" => Ok(Self::Production),
+            "staging" => Ok(Self::Staging),
+            "local" => Ok(Self::Local),
+            _ => match value.split_once("://") {
+                Some(("http" | "https", host)) if !host.is_empty() => {
+                    Ok(Self::Custom(value.trim_end_matches('/').to_owned()))
+                }
+                _ => Err(anyhow!(
+                    "Invalid API environment: {value}. Use production, staging, local or a http(s) URL."
+                )),
+            },
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Production => write!(f, "production"),
+            Self::Staging => write!(f, "staging"),
+            Self::Local => write!(f, "local"),
+            Self::Custom(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_parse_environment() {
+        assert_eq!(
+            "staging".parse::<Environment>().unwrap(),
+            Environment::Staging
+        );
+        let custom: Environment = "http://127.0.0.1:3000/".parse().unwrap();
+        assert_eq!(
+            custom,
+            Environment::Custom("http://127.0.0.1:3000".to_owned())
+        );
+        assert_eq!(custom.protocol(), "http");
+        assert_eq!(custom.host(), "127.0.0.1:3000");
+        assert!("ftp://example.com".parse::<Environment>().is_err());
+    }
+}
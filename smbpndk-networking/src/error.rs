@@ -0,0 +1,193 @@
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use serde::Deserialize;
+use smbpndk_model::account::ErrorCode;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Errors returned by the SmbPndk API, parsed from its error envelope.
+#[derive(Debug, Error)]
+pub enum SmbError {
+    #[error("You are not logged in or your session has expired. Login with smb account login.")]
+    Unauthorized,
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{}", validation_message(.message, .errors))]
+    Validation {
+        message: String,
+        /// Messages per field, e.g. `name: ["can't be blank"]`.
+        errors: BTreeMap<String, Vec<String>>,
+    },
+    #[error("Too many requests. Try again {}.", retry_message(.retry_after))]
+    RateLimited {
+        /// Seconds to wait, from the `Retry-After` header.
+        retry_after: Option<u64>,
+    },
+    #[error("Request failed with status {status}: {message}")]
+    Server { status: u16, message: String },
+    #[error("Could not reach the server: {0}")]
+    Transport(#[source] reqwest::Error),
+    #[error("Unexpected response from the server: {0}")]
+    Decode(String),
+    #[error("{message}")]
+    Account { code: ErrorCode, message: String },
+}
+
+impl SmbError {
+    /// Turns an unsuccessful response into an error, using its body when there is one.
+    pub async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        match response.text().await {
+            Ok(body) => Self::from_parts(status, retry_after, &body),
+            Err(e) => e.into(),
+        }
+    }
+
+    pub fn from_parts(status: StatusCode, retry_after: Option<u64>, body: &str) -> Self {
+        let envelope: ErrorEnvelope = serde_json::from_str(body).unwrap_or_default();
+        let message = envelope.message().unwrap_or_else(|| {
+            status
+                .canonical_reason()
+                .unwrap_or("Unknown error")
+                .to_owned()
+        });
+
+        if let Some(code) = envelope.error_code {
+            return SmbError::Account { code, message };
+        }
+        match status {
+            StatusCode::UNAUTHORIZED => SmbError::Unauthorized,
+            StatusCode::NOT_FOUND => SmbError::NotFound(message),
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => SmbError::Validation {
+                message,
+                errors: envelope.errors.into_fields(),
+            },
+            StatusCode::TOO_MANY_REQUESTS => SmbError::RateLimited { retry_after },
+            _ => SmbError::Server {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+
+    /// Failures that may go away when the request is sent again, like a dropped connection.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SmbError::Transport(_) => true,
+            SmbError::Server { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SmbError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_decode() {
+            SmbError::Decode(error.to_string())
+        } else {
+            SmbError::Transport(error)
+        }
+    }
+}
+
+// Private functions
+
+/// The error body of the API. Older endpoints use `error`, newer ones `message`.
+#[derive(Debug, Default, Deserialize)]
+struct ErrorEnvelope {
+    message: Option<String>,
+    error: Option<String>,
+    error_code: Option<ErrorCode>,
+    #[serde(default)]
+    errors: ErrorDetails,
+}
+
+impl ErrorEnvelope {
+    fn message(&self) -> Option<String> {
+        self.message.clone().or_else(|| self.error.clone())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+enum ErrorDetails {
+    #[default]
+    None,
+    Fields(BTreeMap<String, Vec<String>>),
+    List(Vec<String>),
+}
+
+impl ErrorDetails {
+    fn into_fields(self) -> BTreeMap<String, Vec<String>> {
+        match self {
+            ErrorDetails::None => BTreeMap::new(),
+            ErrorDetails::Fields(fields) => fields,
+            ErrorDetails::List(messages) => BTreeMap::from([("base".to_owned(), messages)]),
+        }
+    }
+}
+
+fn validation_message(message: &str, errors: &BTreeMap<String, Vec<String>>) -> String {
+    let mut lines = vec![message.to_owned()];
+    for (field, messages) in errors {
+        for field_message in messages {
+            if field == "base" {
+                lines.push(format!("  {field_message}"));
+            } else {
+                lines.push(format!("  {field} {field_message}"));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn retry_message(retry_after: &Option<u64>) -> String {
+    match retry_after {
+        Some(seconds) => format!("in {seconds} seconds"),
+        None => "later".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_envelope() {
+        let error = SmbError::from_parts(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            None,
+            r#"{"message": "Validation failed.", "errors": {"name": ["can't be blank"]}}"#,
+        );
+        assert_eq!(
+            error.to_string(),
+            "Validation failed.\n  name can't be blank"
+        );
+
+        let error = SmbError::from_parts(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            None,
+            r#"{"message": "Email not verified.", "error_code": 1001}"#,
+        );
+        assert!(matches!(
+            error,
+            SmbError::Account {
+                code: ErrorCode::EmailUnverified,
+                ..
+            }
+        ));
+
+        let error = SmbError::from_parts(StatusCode::NOT_FOUND, None, "<html></html>");
+        assert_eq!(error.to_string(), "Not Found");
+
+        let error = SmbError::from_parts(StatusCode::TOO_MANY_REQUESTS, Some(30), "");
+        assert_eq!(
+            error.to_string(),
+            "Too many requests. Try again in 30 seconds."
+        );
+    }
+}
@@ -1,45 +1,52 @@
+pub mod client;
 pub mod constants;
+pub mod credentials;
+pub mod environment;
+pub mod error;
 
-use crate::constants::TOKEN_PATH_STR;
+use crate::constants::TOKEN_FILE_NAME;
 use anyhow::{anyhow, Result};
-use constants::{SMB_API_HOST, SMB_API_PROTOCOL, SMB_CLIENT_ID, SMB_CLIENT_SECRET};
+use constants::{SMB_CLIENT_ID, SMB_CLIENT_SECRET};
+use credentials::{CredentialStore, FileCredentialStore};
+use environment::Environment;
 use log::debug;
+use smbpndk_utils::profile::active_profile_dir;
 use std::path::PathBuf;
 use url_builder::URLBuilder;
 
 pub async fn get_smb_token() -> Result<String> {
-    if let Some(path) = smb_token_file_path() {
-        std::fs::read_to_string(path).map_err(|e| {
-            debug!("Error while reading token: {}", &e);
-            anyhow!("Error while reading token. Are you logged in?")
-        })
-    } else {
-        Err(anyhow!("Failed to get home directory."))
+    match FileCredentialStore::for_active_profile()?.load()? {
+        Some(token) => Ok(token),
+        None => Err(anyhow!("Error while reading token. Are you logged in?")),
     }
 }
 
+/// The token file of the active profile, if the user is logged in.
 pub fn smb_token_file_path() -> Option<PathBuf> {
-    match home::home_dir() {
-        Some(path) => {
-            debug!("Home directory: {}.", path.to_str().unwrap());
-            let token_path = path.join(TOKEN_PATH_STR);
+    match smb_token_path() {
+        Ok(token_path) => {
             if token_path.exists() && token_path.is_file() {
                 return Some(token_path);
             }
             None
         }
-        None => {
-            debug!("Failed to get home directory.");
+        Err(e) => {
+            debug!("Failed to get token path: {e}");
             None
         }
     }
 }
 
-pub fn smb_base_url_builder() -> URLBuilder {
+/// Where the token of the active profile is stored, whether or not it exists yet.
+pub fn smb_token_path() -> Result<PathBuf> {
+    Ok(active_profile_dir()?.join(TOKEN_FILE_NAME))
+}
+
+pub fn smb_base_url_builder(environment: &Environment) -> URLBuilder {
     let mut url_builder = URLBuilder::new();
     url_builder
-        .set_protocol(SMB_API_PROTOCOL)
-        .set_host(SMB_API_HOST)
+        .set_protocol(environment.protocol())
+        .set_host(environment.host())
         .add_param("client_id", SMB_CLIENT_ID)
         .add_param("client_secret", SMB_CLIENT_SECRET);
     url_builder
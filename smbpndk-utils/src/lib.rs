@@ -1,9 +1,12 @@
-use std::{fs::OpenOptions, io::Write};
+pub mod profile;
 
 use anyhow::{anyhow, Result};
 use log::debug;
 use regex::Regex;
 use smbpndk_model::project::Config;
+use std::{fs::OpenOptions, io::Write};
+
+const CONFIG_FILE_NAME: &str = "config.json";
 
 pub fn email_validation(input: &str) -> Result<(), &'static str> {
     let email_regex = Regex::new(
@@ -19,50 +22,48 @@ pub fn email_validation(input: &str) -> Result<(), &'static str> {
 }
 
 pub async fn get_config() -> Result<Config> {
-    match home::home_dir() {
-        Some(mut path) => {
-            path.push(".smb/config.json");
-            if !path.exists() {
-                let config = Config {
-                    current_project: None,
-                    current_auth_app: None,
-                };
-                return Ok(config);
-            }
-            let config_string = std::fs::read_to_string(path).map_err(|e| {
-                debug!("Error while reading config file: {}", &e);
-                anyhow!("Error while reading config file. Are you logged in?")
-            })?;
-            let config: Config = serde_json::from_str(&config_string).map_err(|e| {
-                debug!("Error while parsing config: {}", &e);
-                anyhow!("Error while parsing config. Are you logged in?")
-            })?;
+    get_profile_config(&profile::active_profile()).await
+}
 
-            Ok(config)
-        }
-        None => {
-            let config = Config {
-                current_project: None,
-                current_auth_app: None,
-            };
-            Ok(config)
-        }
+pub async fn get_profile_config(profile_name: &str) -> Result<Config> {
+    let path = profile::profile_dir(profile_name)?.join(CONFIG_FILE_NAME);
+    if !path.exists() {
+        let config = Config {
+            current_project: None,
+            current_auth_app: None,
+            environment: None,
+        };
+        return Ok(config);
     }
+    let config_string = std::fs::read_to_string(path).map_err(|e| {
+        debug!("Error while reading config file: {}", &e);
+        anyhow!("Error while reading config file. Are you logged in?")
+    })?;
+    let config: Config = serde_json::from_str(&config_string).map_err(|e| {
+        debug!("Error while parsing config: {}", &e);
+        anyhow!("Error while parsing config. Are you logged in?")
+    })?;
+
+    Ok(config)
 }
 
 pub fn write_config(config: Config) -> Result<Config> {
-    match home::home_dir() {
-        Some(path) => {
-            debug!("{}", path.to_str().unwrap());
-            let mut file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open([path.to_str().unwrap(), "/.smb/config.json"].join(""))?;
-            let json = serde_json::to_string(&config)?;
-            file.write_all(json.as_bytes())?;
+    write_profile_config(&profile::active_profile(), config)
+}
 
-            Ok(config)
-        }
-        None => Err(anyhow!("Error getting home directory.")),
+pub fn write_profile_config(profile_name: &str, config: Config) -> Result<Config> {
+    let path = profile::profile_dir(profile_name)?.join(CONFIG_FILE_NAME);
+    debug!("{}", path.display());
+    if let Some(dir) = path.parent() {
+        profile::create_private_dir(dir)?;
     }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)?;
+    let json = serde_json::to_string(&config)?;
+    file.write_all(json.as_bytes())?;
+
+    Ok(config)
 }
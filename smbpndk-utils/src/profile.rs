@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+pub const DEFAULT_PROFILE: &str = "default";
+pub const SMB_PROFILE_ENV: &str = "SMB_PROFILE";
+
+const SMB_DIR: &str = ".smb";
+const PROFILES_DIR: &str = "profiles";
+const CURRENT_PROFILE_FILE: &str = "profile";
+
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Use `name` as the active profile for the rest of this process, e.g. from `--profile`.
+pub fn set_profile_override(name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    PROFILE_OVERRIDE
+        .set(name.to_owned())
+        .map_err(|_| anyhow!("The active profile has already been set."))
+}
+
+/// The active profile: `--profile`, then `SMB_PROFILE`, then the one set with `smb profile switch`.
+pub fn active_profile() -> String {
+    if let Some(name) = PROFILE_OVERRIDE.get() {
+        return name.clone();
+    }
+    if let Ok(name) = std::env::var(SMB_PROFILE_ENV) {
+        if !name.trim().is_empty() {
+            return name.trim().to_owned();
+        }
+    }
+    smb_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(CURRENT_PROFILE_FILE)).ok())
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_owned())
+}
+
+/// The `~/.smb` directory.
+pub fn smb_dir() -> Result<PathBuf> {
+    match home::home_dir() {
+        Some(path) => Ok(path.join(SMB_DIR)),
+        None => Err(anyhow!("Failed to get home directory.")),
+    }
+}
+
+/// The directory holding the token and config of a profile.
+///
+/// The default profile lives directly in `~/.smb` so existing logins keep working.
+pub fn profile_dir(name: &str) -> Result<PathBuf> {
+    validate_profile_name(name)?;
+    let smb_dir = smb_dir()?;
+    if name == DEFAULT_PROFILE {
+        Ok(smb_dir)
+    } else {
+        Ok(smb_dir.join(PROFILES_DIR).join(name))
+    }
+}
+
+/// Creates `dir` if needed and makes it accessible by the current user only.
+pub fn create_private_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+pub fn active_profile_dir() -> Result<PathBuf> {
+    profile_dir(&active_profile())
+}
+
+pub fn profile_exists(name: &str) -> Result<bool> {
+    Ok(name == DEFAULT_PROFILE || profile_dir(name)?.is_dir())
+}
+
+/// All profiles, the default one first.
+pub fn list_profiles() -> Result<Vec<String>> {
+    let mut profiles = vec![];
+    let profiles_dir = smb_dir()?.join(PROFILES_DIR);
+    if profiles_dir.is_dir() {
+        for entry in fs::read_dir(profiles_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    profiles.push(name.to_owned());
+                }
+            }
+        }
+    }
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_owned());
+    Ok(profiles)
+}
+
+pub fn add_profile(name: &str) -> Result<PathBuf> {
+    if profile_exists(name)? {
+        return Err(anyhow!("Profile {name} already exists."));
+    }
+    let dir = profile_dir(name)?;
+    create_private_dir(&smb_dir()?)?;
+    create_private_dir(&dir)?;
+    debug!("Profile {} created at {}.", name, dir.display());
+    Ok(dir)
+}
+
+pub fn remove_profile(name: &str) -> Result<()> {
+    if name == DEFAULT_PROFILE {
+        return Err(anyhow!("The default profile cannot be removed."));
+    }
+    if !profile_exists(name)? {
+        return Err(anyhow!("Profile {name} does not exist."));
+    }
+    fs::remove_dir_all(profile_dir(name)?)?;
+
+    // Fall back to the default profile if the removed one was selected.
+    let current_profile_path = smb_dir()?.join(CURRENT_PROFILE_FILE);
+    if let Ok(current) = fs::read_to_string(&current_profile_path) {
+        if current.trim() == name {
+            fs::remove_file(current_profile_path)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn switch_profile(name: &str) -> Result<()> {
+    if !profile_exists(name)? {
+        return Err(anyhow!(
+            "Profile {name} does not exist. Create it with smb profile add {name}."
+        ));
+    }
+    let smb_dir = smb_dir()?;
+    create_private_dir(&smb_dir)?;
+    fs::write(smb_dir.join(CURRENT_PROFILE_FILE), name)?;
+    Ok(())
+}
+
+fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Invalid profile name: {name}. Use letters, numbers, - and _ only."
+        ))
+    }
+}